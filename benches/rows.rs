@@ -0,0 +1,102 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use scaling_dollop::dbf::mapped::MappedDbfReader;
+use scaling_dollop::dbf::reader::DbfReader;
+use std::fs::File;
+use std::hint::black_box;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+const RECORDS: u32 = 2_000_000;
+
+/// Writes a dBase table with a name, a price and a date in every record
+fn write_table() -> std::io::Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("dollop-bench-{RECORDS}.dbf"));
+    if path.is_file() {
+        return Ok(path);
+    }
+
+    let mut file = BufWriter::new(File::create(&path)?);
+    let mut header = vec![0x03, 124, 1, 1];
+    header.extend(RECORDS.to_le_bytes());
+    header.extend((32u16 * 4 + 1).to_le_bytes());
+    header.extend(39u16.to_le_bytes());
+    header.resize(32, 0x00);
+
+    for (name, field_type, length, decimal) in [
+        (&b"NAME"[..], b'C', 20, 0),
+        (b"PRICE", b'N', 10, 2),
+        (b"UPDATED", b'D', 8, 0),
+    ] {
+        let mut descriptor = name.to_vec();
+        descriptor.resize(11, 0x00);
+        descriptor.push(field_type);
+        descriptor.extend([0x00; 4]);
+        descriptor.extend([length, decimal]);
+        descriptor.resize(32, 0x00);
+        header.extend(descriptor);
+    }
+    header.push(0x0d);
+    file.write_all(&header)?;
+
+    for record in 0..RECORDS {
+        let name = format!("Product {record}");
+        let price = format!("{}.{:02}", record % 10_000, record % 100);
+        write!(file, " {name:<20}{price:>10}20240101")?;
+    }
+    file.write_all(&[0x1a])?;
+    file.flush()?;
+
+    Ok(path)
+}
+
+fn scan(c: &mut Criterion) {
+    let path = write_table().expect("benchmark table");
+
+    let mut group = c.benchmark_group("scan");
+    group.sample_size(10);
+
+    group.bench_function("rows", |b| {
+        b.iter(|| {
+            let mut dbf = DbfReader::from_reader(File::open(&path).unwrap()).unwrap();
+            for row in dbf.rows() {
+                black_box(row.unwrap().get("PRICE").unwrap());
+            }
+        })
+    });
+
+    group.bench_function("buffered_rows", |b| {
+        b.iter(|| {
+            let mut dbf = DbfReader::from_reader(File::open(&path).unwrap()).unwrap();
+            let mut rows = dbf.buffered_rows();
+            while let Some(row) = rows.next_row() {
+                black_box(row.unwrap().get("PRICE").unwrap());
+            }
+        })
+    });
+
+    group.bench_function("buffered_rows_by_handle", |b| {
+        b.iter(|| {
+            let mut dbf = DbfReader::from_reader(File::open(&path).unwrap()).unwrap();
+            let price = dbf.column("PRICE").unwrap();
+            let mut rows = dbf.buffered_rows();
+            while let Some(row) = rows.next_row() {
+                black_box(row.unwrap().get_by_handle(price).unwrap());
+            }
+        })
+    });
+
+    group.bench_function("mapped_rows_by_handle", |b| {
+        b.iter(|| {
+            let dbf = MappedDbfReader::open(&path).unwrap();
+            let price = dbf.column("PRICE").unwrap();
+            for row in dbf.rows() {
+                black_box(row.unwrap().get_by_handle(price).unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, scan);
+criterion_main!(benches);
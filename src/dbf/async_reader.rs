@@ -0,0 +1,354 @@
+use crate::dbf::codepage::CodePage;
+use crate::dbf::header::{Field, Header, MemoLayout, smt_not_supported, visible_fields};
+use crate::dbf::properties::FieldProperty;
+use crate::dbf::reader::{HEADER_START_SIZE, find_memo, header_size, read_table_info};
+use crate::dbf::rows::{BLOCK_SIZE, ColumnIndex, Row, RowFilter, RowRef, find_column};
+use crate::errors::Error;
+use crate::errors::Error::RecordOutOfBounds;
+use crate::memo::SharedMemo;
+use crate::memo::async_memo::{
+    AsyncDbt3Reader, AsyncDbt4Reader, AsyncFptReader, AsyncMemoRead, FetchedMemos,
+};
+use std::collections::HashMap;
+use std::io::{Cursor, SeekFrom};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::fs::File;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+/// A DBF table reader for async code, rows are read in order with `next_row`.
+///
+/// Memos are read with the row they belong to, so rows read them
+/// with `get` like any other column and never block
+pub struct AsyncDbfReader<R: AsyncRead + AsyncSeek + Unpin> {
+    reader: R,
+    memo: Option<Box<dyn AsyncMemoRead>>,
+    header: Header,
+    code_page: CodePage,
+    fields: Arc<Vec<Field>>,
+    properties: Vec<FieldProperty>,
+    filter: RowFilter,
+    buffer: Vec<u8>,
+    position: usize,
+    current: u32,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncDbfReader<R> {
+    /// Creates a DBF parser from a reader
+    pub async fn from_reader(mut reader: R) -> Result<Self, Error> {
+        // the header is read at once and parsed like `DbfReader` does
+        reader.seek(SeekFrom::Start(0)).await?;
+        let mut data = vec![0u8; HEADER_START_SIZE];
+        reader.read_exact(&mut data).await?;
+
+        data.resize(header_size(&data)?, 0);
+        reader.read_exact(&mut data[HEADER_START_SIZE..]).await?;
+        let table = read_table_info(&mut Cursor::new(data))?;
+
+        Ok(Self {
+            reader,
+            header: table.header,
+            code_page: table.code_page,
+            memo: None,
+            fields: Arc::new(table.fields),
+            properties: table.properties,
+            filter: RowFilter::All,
+            buffer: Vec::new(),
+            position: 0,
+            current: 0,
+        })
+    }
+
+    /// Sets a memo reader for memo fields
+    pub fn with_memo(mut self, memo: impl AsyncMemoRead + 'static) -> Self {
+        self.memo = Some(Box::new(memo));
+        self
+    }
+
+    /// Table information from the header
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Sets the code page used for text, overriding the one from the header,
+    /// tables without a language driver we know are read as CP437
+    pub fn with_code_page(mut self, code_page: CodePage) -> Self {
+        self.code_page = code_page;
+        self
+    }
+
+    /// Code page used for text in this table and its memos
+    pub fn code_page(&self) -> CodePage {
+        self.code_page
+    }
+
+    /// Fields defined in this DBF table
+    /// system fields (like `_NullFlags` in VFP) are not included
+    pub fn fields(&self) -> &[Field] {
+        visible_fields(&self.fields)
+    }
+
+    /// Every field defined in this DBF table, including system fields
+    pub fn all_fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Finds a column by its name once, rows read it with `get_by_handle`
+    pub fn column(&self, name: &str) -> Result<ColumnIndex, Error> {
+        find_column(&self.fields, name)
+    }
+
+    /// Field properties (required, min, max, default and custom ones),
+    /// only dBase 7 tables have them
+    pub fn properties(&self) -> &[FieldProperty] {
+        &self.properties
+    }
+
+    /// Sets which rows we get depending on their deleted flag,
+    /// by default every row is returned
+    pub fn with_filter(mut self, filter: RowFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Which rows we get depending on their deleted flag
+    pub fn filter(&self) -> RowFilter {
+        self.filter
+    }
+
+    /// Moves to the given record, the next row read is that one,
+    /// records are numbered from 0
+    pub fn seek_record(&mut self, record: u32) -> Result<(), Error> {
+        let total = self.header.num_records;
+        if record > total {
+            return Err(RecordOutOfBounds(record, total));
+        }
+
+        self.current = record;
+        self.buffer.clear();
+        self.position = 0;
+        Ok(())
+    }
+
+    /// Reads the next row in the table, deleted rows are
+    /// returned depending on `filter`, `None` after the last one
+    pub async fn next_row(&mut self) -> Option<Result<Row, Error>> {
+        loop {
+            if self.current >= self.header.num_records {
+                return None;
+            }
+
+            if self.position >= self.buffer.len()
+                && let Err(err) = self.read_block().await
+            {
+                self.current = self.header.num_records;
+                return Some(Err(err));
+            }
+
+            let start = self.position;
+            let record = self.current;
+            self.position += self.header.record_length as usize;
+            self.current += 1;
+
+            if self.filter.accepts(self.buffer[start]) {
+                let data = self.buffer[start..self.position].to_vec();
+                return Some(self.make_row(record, data).await);
+            }
+        }
+    }
+
+    /// Reads a single record even if it is deleted, records are numbered from 0
+    pub async fn record(&mut self, record: u32) -> Result<Row, Error> {
+        let total = self.header.num_records;
+        if record >= total {
+            return Err(RecordOutOfBounds(record, total));
+        }
+
+        let record_size = self.header.record_length as u64;
+        let position = (self.header.record_start as u64) + record_size * (record as u64);
+
+        // rows keep going from their buffer, blocks seek to where they start
+        self.reader.seek(SeekFrom::Start(position)).await?;
+        let mut data = vec![0u8; record_size as usize];
+        self.reader.read_exact(&mut data).await?;
+
+        self.make_row(record, data).await
+    }
+
+    async fn read_block(&mut self) -> Result<(), Error> {
+        let record_size = self.header.record_length as usize;
+        let records = (BLOCK_SIZE / record_size)
+            .max(1)
+            .min((self.header.num_records - self.current) as usize);
+
+        let position = (self.header.record_start as u64)
+            + (self.header.record_length as u64) * (self.current as u64);
+        self.reader.seek(SeekFrom::Start(position)).await?;
+
+        self.buffer.resize(records * record_size, 0);
+        self.reader.read_exact(&mut self.buffer).await?;
+        self.position = 0;
+
+        Ok(())
+    }
+
+    /// Makes a row with the memos it points to already read
+    async fn make_row(&mut self, record: u32, data: Vec<u8>) -> Result<Row, Error> {
+        let Some(memo) = self.memo.as_mut() else {
+            return Ok(Row::new(
+                Arc::clone(&self.fields),
+                None,
+                self.code_page,
+                record,
+                data,
+            ));
+        };
+
+        let no_memo = None;
+        let row = RowRef::new(&self.fields, &no_memo, self.code_page, record, &data);
+        let mut memos = HashMap::new();
+        for field in self.fields.iter() {
+            if !field.field_type().is_memo() {
+                continue;
+            }
+            if let Some(block) = row.field_memo_block(field)?
+                && !memos.contains_key(&block)
+            {
+                memos.insert(block, memo.read_typed_memo(block).await?);
+            }
+        }
+
+        let memos: SharedMemo = Arc::new(Mutex::new(FetchedMemos {
+            memos,
+            next_block: memo.next_available_block(),
+            soft_returns: memo.has_soft_returns(),
+        }));
+
+        Ok(Row::new(
+            Arc::clone(&self.fields),
+            Some(memos),
+            self.code_page,
+            record,
+            data,
+        ))
+    }
+}
+
+impl AsyncDbfReader<File> {
+    /// Opens a DBF table from a path, when the table has memo fields
+    /// its memo file (DBT or FPT) is found and attached as well
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let dbf = Self::from_reader(File::open(path).await?).await?;
+
+        let Some((memo_path, layout)) = find_memo(path, dbf.header.version, &dbf.fields)? else {
+            return Ok(dbf);
+        };
+
+        let memo = File::open(memo_path).await?;
+        let dbf = match layout {
+            MemoLayout::Dbt3 => dbf.with_memo(AsyncDbt3Reader::from_reader(memo).await?),
+            MemoLayout::Dbt4 => dbf.with_memo(AsyncDbt4Reader::from_reader(memo).await?),
+            MemoLayout::Fpt => dbf.with_memo(AsyncFptReader::from_reader(memo).await?),
+            MemoLayout::Smt => return Err(smt_not_supported()),
+        };
+
+        Ok(dbf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dbf::async_reader::AsyncDbfReader;
+    use crate::dbf::reader::DbfReader;
+    use crate::dbf::rows::{RowFilter, Value};
+    use crate::errors::Error::{MemoNotFound, RecordOutOfBounds};
+    use crate::sample_path;
+    use tokio::fs::File;
+
+    #[tokio::test]
+    async fn async_rows_match_rows() -> anyhow::Result<()> {
+        for name in [
+            "db3.dbf",
+            "vfp.dbf",
+            "db3memo.dbf",
+            "db4memo.dbf",
+            "vfpmemo.dbf",
+        ] {
+            let mut dbf = DbfReader::open(sample_path(name))?;
+            let mut async_dbf = AsyncDbfReader::open(sample_path(name)).await?;
+            assert_eq!(dbf.all_fields().len(), async_dbf.all_fields().len());
+
+            let mut rows = dbf.rows();
+            while let Some(row) = async_dbf.next_row().await {
+                let (expected, row) = (rows.next().unwrap()?, row?);
+                assert_eq!(expected.record(), row.record());
+
+                // memos were read ahead, values and errors must not change
+                for index in 0..expected.fields().len() {
+                    let value = row.get_by_index(index).map_err(|err| err.to_string());
+                    let expected = expected.get_by_index(index).map_err(|err| err.to_string());
+                    assert_eq!(
+                        expected,
+                        value,
+                        "{name} record {} field {index}",
+                        row.record()
+                    );
+                }
+            }
+            assert!(rows.next().is_none());
+        }
+
+        let mut async_dbf = AsyncDbfReader::open(sample_path("db3memo.dbf")).await?;
+        let row = async_dbf.next_row().await.unwrap()?;
+        assert_eq!(
+            Value::Memo("Just a brief note.".to_string()),
+            row.get("NOTES")?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn async_records() -> anyhow::Result<()> {
+        let file = File::open(sample_path("db3.dbf")).await?;
+        let mut dbf = AsyncDbfReader::from_reader(file)
+            .await?
+            .with_filter(RowFilter::Live);
+
+        let row = dbf.record(2).await?;
+        assert!(row.is_deleted());
+        assert_eq!("Thingamajig", row.get_str("NAME")?);
+
+        dbf.seek_record(4)?;
+        let mut records = vec![];
+        while let Some(row) = dbf.next_row().await {
+            records.push(row?.record());
+        }
+        assert_eq!(vec![4, 6, 7], records);
+
+        assert!(matches!(dbf.record(8).await, Err(RecordOutOfBounds(8, 8))));
+        assert!(matches!(dbf.seek_record(9), Err(RecordOutOfBounds(9, 8))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn async_rows_with_memo() -> anyhow::Result<()> {
+        let mut dbf = AsyncDbfReader::open(sample_path("vfpmemo.dbf")).await?;
+        let row = dbf.next_row().await.unwrap()?;
+        assert_eq!(
+            Value::Memo("Just a brief note.".to_string()),
+            row.get("MEMO")?
+        );
+
+        // without a memo reader there is nothing to read
+        let file = File::open(sample_path("vfpmemo.dbf")).await?;
+        let mut dbf = AsyncDbfReader::from_reader(file).await?;
+        let row = dbf.next_row().await.unwrap()?;
+        assert!(matches!(row.get("MEMO"), Err(MemoNotFound(_))));
+        assert!(row.memo_block("MEMO")?.is_some());
+
+        Ok(())
+    }
+}
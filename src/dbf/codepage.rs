@@ -0,0 +1,321 @@
+use crate::errors::Error;
+use crate::errors::Error::Fieldvalue;
+use encoding_rs::Encoding;
+use oem_cp::code_table::{
+    DECODING_TABLE_CP437, DECODING_TABLE_CP737, DECODING_TABLE_CP850, DECODING_TABLE_CP852,
+    DECODING_TABLE_CP857, DECODING_TABLE_CP860, DECODING_TABLE_CP861, DECODING_TABLE_CP863,
+    DECODING_TABLE_CP865, DECODING_TABLE_CP866,
+};
+use oem_cp::code_table_type::TableType;
+use oem_cp::code_table_type::TableType::{Complete, Incomplete};
+use std::borrow::Cow;
+use strum::Display;
+
+/// Code page used to encode text in a table and its memo file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Display)]
+pub enum CodePage {
+    /// Not a real DBF code page, for tables written by tools storing UTF-8
+    #[strum(to_string = "UTF-8")]
+    Utf8,
+    // MS-DOS
+    /// Used for tables without a language driver or with one we don't know,
+    /// most of them come from MS-DOS applications
+    #[default]
+    #[strum(to_string = "CP437")]
+    Cp437,
+    /// Mazovia, Polish
+    #[strum(to_string = "CP620")]
+    Cp620,
+    #[strum(to_string = "CP737")]
+    Cp737,
+    #[strum(to_string = "CP850")]
+    Cp850,
+    #[strum(to_string = "CP852")]
+    Cp852,
+    #[strum(to_string = "CP857")]
+    Cp857,
+    #[strum(to_string = "CP860")]
+    Cp860,
+    #[strum(to_string = "CP861")]
+    Cp861,
+    #[strum(to_string = "CP863")]
+    Cp863,
+    #[strum(to_string = "CP865")]
+    Cp865,
+    #[strum(to_string = "CP866")]
+    Cp866,
+    /// Kamenický, Czech
+    #[strum(to_string = "CP895")]
+    Cp895,
+    // Windows
+    #[strum(to_string = "CP874")]
+    Cp874,
+    #[strum(to_string = "CP932")]
+    Cp932,
+    #[strum(to_string = "CP936")]
+    Cp936,
+    #[strum(to_string = "CP949")]
+    Cp949,
+    #[strum(to_string = "CP950")]
+    Cp950,
+    #[strum(to_string = "CP1250")]
+    Cp1250,
+    #[strum(to_string = "CP1251")]
+    Cp1251,
+    #[strum(to_string = "CP1252")]
+    Cp1252,
+    #[strum(to_string = "CP1253")]
+    Cp1253,
+    #[strum(to_string = "CP1254")]
+    Cp1254,
+    #[strum(to_string = "CP1255")]
+    Cp1255,
+    #[strum(to_string = "CP1256")]
+    Cp1256,
+    #[strum(to_string = "CP1257")]
+    Cp1257,
+    // Macintosh
+    #[strum(to_string = "CP10000")]
+    Cp10000,
+    #[strum(to_string = "CP10006")]
+    Cp10006,
+    #[strum(to_string = "CP10007")]
+    Cp10007,
+    #[strum(to_string = "CP10029")]
+    Cp10029,
+}
+
+impl CodePage {
+    /// Maps the language driver ID (byte 29 in the header) to its code page,
+    /// returns `None` when the table doesn't set it or we don't know it
+    pub fn from_language_driver(id: u8) -> Option<Self> {
+        let code_page = match id {
+            0x01 | 0x09 | 0x0b | 0x0d | 0x0f | 0x11 | 0x15 | 0x18 | 0x19 | 0x1b => Self::Cp437,
+            0x02 | 0x0a | 0x0e | 0x10 | 0x12 | 0x14 | 0x16 | 0x1a | 0x1d | 0x25 | 0x37 => {
+                Self::Cp850
+            }
+            0x03 | 0x57 | 0x58 | 0x59 => Self::Cp1252,
+            0x04 => Self::Cp10000,
+            0x08 | 0x17 | 0x66 => Self::Cp865,
+            0x13 | 0x7b => Self::Cp932,
+            0x1c | 0x6c => Self::Cp863,
+            0x1f | 0x22 | 0x23 | 0x40 | 0x64 | 0x87 => Self::Cp852,
+            0x24 => Self::Cp860,
+            0x26 | 0x65 => Self::Cp866,
+            0x4d | 0x7a => Self::Cp936,
+            0x4e | 0x79 => Self::Cp949,
+            0x4f | 0x78 => Self::Cp950,
+            0x50 | 0x7c => Self::Cp874,
+            0x67 => Self::Cp861,
+            0x68 => Self::Cp895,
+            0x69 => Self::Cp620,
+            0x6a | 0x86 => Self::Cp737,
+            0x6b | 0x88 => Self::Cp857,
+            0x7d => Self::Cp1255,
+            0x7e => Self::Cp1256,
+            0x96 => Self::Cp10007,
+            0x97 => Self::Cp10029,
+            0x98 => Self::Cp10006,
+            0xc8 => Self::Cp1250,
+            0xc9 => Self::Cp1251,
+            0xca => Self::Cp1254,
+            0xcb => Self::Cp1253,
+            0xcc => Self::Cp1257,
+            _ => return None,
+        };
+
+        Some(code_page)
+    }
+
+    /// Maps a dBase 7 language driver name (e.g. `DB437US0`) to its code page,
+    /// only the common MS-DOS and ANSI drivers are known
+    pub fn from_language_driver_name(name: &str) -> Option<Self> {
+        let code_page = match name.get(..5)? {
+            "DB437" => Self::Cp437,
+            "DB850" => Self::Cp850,
+            "DB852" => Self::Cp852,
+            "DB857" => Self::Cp857,
+            "DB860" => Self::Cp860,
+            "DB861" => Self::Cp861,
+            "DB863" => Self::Cp863,
+            "DB865" => Self::Cp865,
+            "DB866" => Self::Cp866,
+            "DBWIN" => Self::Cp1252,
+            _ => return None,
+        };
+
+        Some(code_page)
+    }
+
+    /// Decodes text stored with this code page
+    pub(crate) fn decode<'a>(&self, bytes: &'a [u8]) -> Result<Cow<'a, str>, Error> {
+        let invalid = || Fieldvalue(format!("invalid field value for text in {self}"));
+
+        // every code page we know agrees with ASCII
+        if *self == Self::Utf8 || bytes.is_ascii() {
+            return std::str::from_utf8(bytes)
+                .map(Cow::Borrowed)
+                .map_err(|_| invalid());
+        }
+
+        let text = match self.oem_table() {
+            Some(table) => table.decode_string_checked(bytes).map(Cow::Owned),
+            None => self.encoding().and_then(|encoding| {
+                encoding.decode_without_bom_handling_and_without_replacement(bytes)
+            }),
+        };
+
+        text.ok_or_else(invalid)
+    }
+
+    /// MS-DOS and some Macintosh code pages are not in encoding_rs,
+    /// we use tables for them
+    fn oem_table(&self) -> Option<TableType> {
+        let table = match self {
+            Self::Cp437 => Complete(&DECODING_TABLE_CP437),
+            Self::Cp620 => Complete(&DECODING_TABLE_CP620),
+            Self::Cp737 => Complete(&DECODING_TABLE_CP737),
+            Self::Cp850 => Complete(&DECODING_TABLE_CP850),
+            Self::Cp852 => Complete(&DECODING_TABLE_CP852),
+            Self::Cp857 => Incomplete(&DECODING_TABLE_CP857),
+            Self::Cp860 => Complete(&DECODING_TABLE_CP860),
+            Self::Cp861 => Complete(&DECODING_TABLE_CP861),
+            Self::Cp863 => Complete(&DECODING_TABLE_CP863),
+            Self::Cp865 => Complete(&DECODING_TABLE_CP865),
+            Self::Cp866 => Complete(&DECODING_TABLE_CP866),
+            Self::Cp895 => Complete(&DECODING_TABLE_CP895),
+            Self::Cp10006 => Complete(&DECODING_TABLE_CP10006),
+            Self::Cp10029 => Complete(&DECODING_TABLE_CP10029),
+            _ => return None,
+        };
+
+        Some(table)
+    }
+
+    fn encoding(&self) -> Option<&'static Encoding> {
+        let encoding = match self {
+            Self::Cp874 => encoding_rs::WINDOWS_874,
+            Self::Cp932 => encoding_rs::SHIFT_JIS,
+            Self::Cp936 => encoding_rs::GBK,
+            Self::Cp949 => encoding_rs::EUC_KR,
+            Self::Cp950 => encoding_rs::BIG5,
+            Self::Cp1250 => encoding_rs::WINDOWS_1250,
+            Self::Cp1251 => encoding_rs::WINDOWS_1251,
+            Self::Cp1252 => encoding_rs::WINDOWS_1252,
+            Self::Cp1253 => encoding_rs::WINDOWS_1253,
+            Self::Cp1254 => encoding_rs::WINDOWS_1254,
+            Self::Cp1255 => encoding_rs::WINDOWS_1255,
+            Self::Cp1256 => encoding_rs::WINDOWS_1256,
+            Self::Cp1257 => encoding_rs::WINDOWS_1257,
+            Self::Cp10000 => encoding_rs::MACINTOSH,
+            Self::Cp10007 => encoding_rs::X_MAC_CYRILLIC,
+            _ => return None,
+        };
+
+        Some(encoding)
+    }
+}
+
+/// Mazovia, CP437 with Polish letters in place of some symbols
+static DECODING_TABLE_CP620: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'ą', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ć', 'Ä', 'Ą', 'Ę', 'ę', 'ł',
+    'ô', 'ö', 'Ć', 'û', 'ù', 'Ś', 'Ö', 'Ü', '¢', 'Ł', '¥', 'ś', 'ƒ', 'Ź', 'Ż', 'ó', 'Ó', 'ń', 'Ń',
+    'ź', 'ż', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕',
+    '╣', '║', '╗', '╝', '╜', '╛', '┐', '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦',
+    '╠', '═', '╬', '╧', '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐',
+    '▀', 'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', '≡', '±',
+    '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+/// Kamenický, CP437 with Czech and Slovak letters in place of the accented ones
+static DECODING_TABLE_CP895: [char; 128] = [
+    'Č', 'ü', 'é', 'ď', 'ä', 'Ď', 'Ť', 'č', 'ě', 'Ě', 'Ĺ', 'Í', 'ľ', 'ĺ', 'Ä', 'Á', 'É', 'ž', 'Ž',
+    'ô', 'ö', 'Ó', 'ů', 'Ú', 'ý', 'Ö', 'Ü', 'Š', 'Ľ', 'Ý', 'Ř', 'ť', 'á', 'í', 'ó', 'ú', 'ň', 'Ň',
+    'Ů', 'Ô', 'š', 'ř', 'ŕ', 'Ŕ', '¼', '§', '«', '»', '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕',
+    '╣', '║', '╗', '╝', '╜', '╛', '┐', '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦',
+    '╠', '═', '╬', '╧', '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐',
+    '▀', 'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', '≡', '±',
+    '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+/// Macintosh Greek
+static DECODING_TABLE_CP10006: [char; 128] = [
+    'Ä', '¹', '²', 'É', '³', 'Ö', 'Ü', '΅', 'à', 'â', 'ä', '΄', '¨', 'ç', 'é', 'è', 'ê', 'ë', '£',
+    '™', 'î', 'ï', '•', '½', '‰', 'ô', 'ö', '¦', '€', 'ù', 'û', 'ü', '†', 'Γ', 'Δ', 'Θ', 'Λ', 'Ξ',
+    'Π', 'ß', '®', '©', 'Σ', 'Ϊ', '§', '≠', '°', '·', 'Α', '±', '≤', '≥', '¥', 'Β', 'Ε', 'Ζ', 'Η',
+    'Ι', 'Κ', 'Μ', 'Φ', 'Ϋ', 'Ψ', 'Ω', 'ά', 'Ν', '¬', 'Ο', 'Ρ', '≈', 'Τ', '«', '»', '…', '\u{a0}',
+    'Υ', 'Χ', 'Ά', 'Έ', 'œ', '–', '―', '“', '”', '‘', '’', '÷', 'Ή', 'Ί', 'Ό', 'Ύ', 'έ', 'ή', 'ί',
+    'ό', 'Ώ', 'ύ', 'α', 'β', 'ψ', 'δ', 'ε', 'φ', 'γ', 'η', 'ι', 'ξ', 'κ', 'λ', 'μ', 'ν', 'ο', 'π',
+    'ώ', 'ρ', 'σ', 'τ', 'θ', 'ω', 'ς', 'χ', 'υ', 'ζ', 'ϊ', 'ϋ', 'ΐ', 'ΰ', '\u{ad}',
+];
+
+/// Macintosh Central European
+static DECODING_TABLE_CP10029: [char; 128] = [
+    'Ä', 'Ā', 'ā', 'É', 'Ą', 'Ö', 'Ü', 'á', 'ą', 'Č', 'ä', 'č', 'Ć', 'ć', 'é', 'Ź', 'ź', 'Ď', 'í',
+    'ď', 'Ē', 'ē', 'Ė', 'ó', 'ė', 'ô', 'ö', 'õ', 'ú', 'Ě', 'ě', 'ü', '†', '°', 'Ę', '£', '§', '•',
+    '¶', 'ß', '®', '©', '™', 'ę', '¨', '≠', 'ģ', 'Į', 'į', 'Ī', '≤', '≥', 'ī', 'Ķ', '∂', '∑', 'ł',
+    'Ļ', 'ļ', 'Ľ', 'ľ', 'Ĺ', 'ĺ', 'Ņ', 'ņ', 'Ń', '¬', '√', 'ń', 'Ň', '∆', '«', '»', '…', '\u{a0}',
+    'ň', 'Ő', 'Õ', 'ő', 'Ō', '–', '—', '“', '”', '‘', '’', '÷', '◊', 'ō', 'Ŕ', 'ŕ', 'Ř', '‹', '›',
+    'ř', 'Ŗ', 'ŗ', 'Š', '‚', '„', 'š', 'Ś', 'ś', 'Á', 'Ť', 'ť', 'Í', 'Ž', 'ž', 'Ū', 'Ó', 'Ô', 'ū',
+    'Ů', 'Ú', 'ů', 'Ű', 'ű', 'Ų', 'ų', 'Ý', 'ý', 'ķ', 'Ż', 'Ł', 'ż', 'Ģ', 'ˇ',
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::dbf::codepage::CodePage;
+
+    #[test]
+    fn language_driver_to_code_page() {
+        assert_eq!(None, CodePage::from_language_driver(0x00));
+        assert_eq!(None, CodePage::from_language_driver(0xf0));
+        assert_eq!(Some(CodePage::Cp437), CodePage::from_language_driver(0x01));
+        assert_eq!(Some(CodePage::Cp850), CodePage::from_language_driver(0x02));
+        assert_eq!(Some(CodePage::Cp1252), CodePage::from_language_driver(0x03));
+        assert_eq!(Some(CodePage::Cp852), CodePage::from_language_driver(0x64));
+        assert_eq!(Some(CodePage::Cp866), CodePage::from_language_driver(0x65));
+        assert_eq!(Some(CodePage::Cp895), CodePage::from_language_driver(0x68));
+        assert_eq!(Some(CodePage::Cp620), CodePage::from_language_driver(0x69));
+        assert_eq!(
+            Some(CodePage::Cp10029),
+            CodePage::from_language_driver(0x97)
+        );
+        assert_eq!(
+            Some(CodePage::Cp10006),
+            CodePage::from_language_driver(0x98)
+        );
+        assert_eq!(Some(CodePage::Cp1250), CodePage::from_language_driver(0xc8));
+    }
+
+    #[test]
+    fn language_driver_name_to_code_page() {
+        assert_eq!(
+            Some(CodePage::Cp437),
+            CodePage::from_language_driver_name("DB437US0")
+        );
+        assert_eq!(
+            Some(CodePage::Cp1252),
+            CodePage::from_language_driver_name("DBWINUS0")
+        );
+        assert_eq!(None, CodePage::from_language_driver_name(""));
+    }
+
+    #[test]
+    fn decode_code_pages() -> anyhow::Result<()> {
+        assert_eq!("Müller", CodePage::Cp437.decode(b"M\x81ller")?);
+        assert_eq!("Ñandú", CodePage::Cp850.decode(b"\xa5and\xa3")?);
+        assert_eq!("Łódź", CodePage::Cp852.decode(b"\x9d\xa2d\xab")?);
+        assert_eq!("Мир", CodePage::Cp866.decode(b"\x8c\xa8\xe0")?);
+        assert_eq!("Łódź", CodePage::Cp1250.decode(b"\xa3\xf3d\x9f")?);
+        assert_eq!("Café", CodePage::Cp1252.decode(b"Caf\xe9")?);
+        assert_eq!("Łódź", CodePage::Cp620.decode(b"\x9c\xa2d\xa6")?);
+        assert_eq!("Plzeň", CodePage::Cp895.decode(b"Plze\xa4")?);
+        assert_eq!("Łódź", CodePage::Cp10029.decode(b"\xfc\x97d\x90")?);
+        assert_eq!("Αθήνα", CodePage::Cp10006.decode(b"\xb0\xf5\xdc\xee\xe1")?);
+
+        // UTF-8 is strict
+        assert!(CodePage::Utf8.decode(b"Caf\xe9").is_err());
+        assert_eq!("Café", CodePage::Utf8.decode("Café".as_bytes())?);
+
+        Ok(())
+    }
+}
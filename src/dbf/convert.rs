@@ -0,0 +1,122 @@
+use crate::dbf::rows::Value;
+use crate::errors::Error;
+use crate::errors::Error::{Deserialize, UnexpectedType};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use time::{Date, PrimitiveDateTime};
+
+/// Names the column in errors from converting or deserializing its value
+pub(crate) fn in_column(column: &str, error: Error) -> Error {
+    match error {
+        UnexpectedType(_, expected) => UnexpectedType(format!("field {column}"), expected),
+        Deserialize(message) => Deserialize(format!("{column}: {message}")),
+        error => error,
+    }
+}
+
+fn unexpected(value: Value, expected: &'static str) -> Error {
+    UnexpectedType(format!("{value:?}"), expected)
+}
+
+impl TryFrom<Value> for String {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Character(text) | Value::Memo(text) => Ok(text),
+            value => Err(unexpected(value, "a string")),
+        }
+    }
+}
+
+impl TryFrom<Value> for Decimal {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Numeric(number) | Value::Currency(number) => Ok(number),
+            Value::Integer(number) => Ok(Decimal::from(number)),
+            value => Err(unexpected(value, "a decimal")),
+        }
+    }
+}
+
+impl TryFrom<Value> for i32 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Integer(number) => Ok(number),
+            // numeric fields without decimals are integers as well
+            Value::Numeric(number) if number.fract().is_zero() => number
+                .to_i32()
+                .ok_or_else(|| unexpected(value, "an integer")),
+            value => Err(unexpected(value, "an integer")),
+        }
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Logical(flag) => Ok(flag),
+            value => Err(unexpected(value, "a boolean")),
+        }
+    }
+}
+
+impl TryFrom<Value> for Date {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Date(date) => Ok(date),
+            value => Err(unexpected(value, "a date")),
+        }
+    }
+}
+
+impl TryFrom<Value> for PrimitiveDateTime {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value {
+            Value::DateTime(date_time) => Ok(date_time),
+            value => Err(unexpected(value, "a date time")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dbf::rows::Value;
+    use crate::errors::Error::UnexpectedType;
+    use rust_decimal::Decimal;
+    use time::{Date, Month};
+
+    #[test]
+    fn convert_values() -> anyhow::Result<()> {
+        assert_eq!("Alpha", String::try_from(Value::Memo("Alpha".into()))?);
+        assert_eq!(Decimal::new(7, 0), Decimal::try_from(Value::Integer(7))?);
+        assert_eq!(150, i32::try_from(Value::Numeric(Decimal::new(150, 0)))?);
+        assert!(bool::try_from(Value::Logical(true))?);
+
+        let date = Date::from_calendar_date(1925, Month::January, 15)?;
+        assert_eq!(date, Date::try_from(Value::Date(date))?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn convert_wrong_values() {
+        // decimals would lose their fraction
+        let error = i32::try_from(Value::Numeric(Decimal::new(2999, 2))).unwrap_err();
+        assert!(matches!(error, UnexpectedType(_, "an integer")));
+        assert_eq!("Numeric(29.99) is not an integer", error.to_string());
+
+        let error = String::try_from(Value::Null).unwrap_err();
+        assert!(matches!(error, UnexpectedType(_, "a string")));
+    }
+}
@@ -0,0 +1,522 @@
+use crate::SliceUntilTerminator;
+use crate::dbf::codepage::CodePage;
+use crate::dbf::reader::DbfReader;
+use crate::dbf::rows::{Row, RowFilter, Value};
+use crate::errors::Error;
+use crate::errors::Error::{FileFormat, TableNotFound};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+
+// ids for the properties we know in the PROPERTY memo
+const PATH: u8 = 0x01;
+const RULE_EXPRESSION: u8 = 0x09;
+const RULE_TEXT: u8 = 0x0a;
+const DEFAULT_VALUE: u8 = 0x0b;
+const CHILD_TAG: u8 = 0x0d;
+const PARENT_TABLE: u8 = 0x12;
+const PARENT_TAG: u8 = 0x13;
+const CAPTION: u8 = 0x38;
+
+/// Length, type and id before each property value
+const PROPERTY_HEADER: usize = 7;
+
+/// A field (column) of a table as the database describes it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DatabaseField {
+    pub(crate) name: String,
+    pub(crate) caption: Option<String>,
+    pub(crate) default_value: Option<String>,
+    pub(crate) rule: Option<String>,
+    pub(crate) rule_text: Option<String>,
+}
+
+impl DatabaseField {
+    /// Returns the long name for this field, up to 128 characters
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the caption shown for this field
+    pub fn caption(&self) -> Option<&str> {
+        self.caption.as_deref()
+    }
+
+    /// Returns the default value, an expression to evaluate
+    pub fn default_value(&self) -> Option<&str> {
+        self.default_value.as_deref()
+    }
+
+    /// Returns the validation rule, an expression to evaluate
+    pub fn rule(&self) -> Option<&str> {
+        self.rule.as_deref()
+    }
+
+    /// Returns the message shown when the validation rule fails
+    pub fn rule_text(&self) -> Option<&str> {
+        self.rule_text.as_deref()
+    }
+}
+
+/// A table belonging to a database
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DatabaseTable {
+    pub(crate) name: String,
+    pub(crate) path: Option<String>,
+    pub(crate) rule: Option<String>,
+    pub(crate) rule_text: Option<String>,
+    pub(crate) fields: Vec<DatabaseField>,
+}
+
+impl DatabaseTable {
+    /// Returns the long name for this table
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path to the DBF file, relative to the database
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Returns the validation rule for records, an expression to evaluate
+    pub fn rule(&self) -> Option<&str> {
+        self.rule.as_deref()
+    }
+
+    /// Returns the message shown when the validation rule fails
+    pub fn rule_text(&self) -> Option<&str> {
+        self.rule_text.as_deref()
+    }
+
+    /// Returns the fields in the same order as in the DBF file
+    pub fn fields(&self) -> &[DatabaseField] {
+        &self.fields
+    }
+}
+
+/// A persistent relation between two tables, the child
+/// table points to the parent one through their index tags
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Relation {
+    pub(crate) child_table: String,
+    pub(crate) child_tag: String,
+    pub(crate) parent_table: String,
+    pub(crate) parent_tag: String,
+}
+
+impl Relation {
+    /// Returns the table holding the reference
+    pub fn child_table(&self) -> &str {
+        &self.child_table
+    }
+
+    /// Returns the index tag used in the child table
+    pub fn child_tag(&self) -> &str {
+        &self.child_tag
+    }
+
+    /// Returns the table being referenced
+    pub fn parent_table(&self) -> &str {
+        &self.parent_table
+    }
+
+    /// Returns the index tag used in the parent table
+    pub fn parent_tag(&self) -> &str {
+        &self.parent_tag
+    }
+}
+
+/// A Visual FoxPro database (DBC), it is a table where each record
+/// is an object (table, field, relation...) with its properties
+#[derive(Clone, Debug)]
+pub struct Database {
+    directory: Option<PathBuf>,
+    tables: Vec<DatabaseTable>,
+    relations: Vec<Relation>,
+}
+
+/// A record in the database table
+struct Object {
+    id: i32,
+    parent: i32,
+    kind: String,
+    name: String,
+    properties: Vec<(u8, Vec<u8>)>,
+}
+
+impl Object {
+    fn property(&self, id: u8, code_page: CodePage) -> Result<Option<String>, Error> {
+        let Some((_, value)) = self.properties.iter().find(|(found, _)| *found == id) else {
+            return Ok(None);
+        };
+
+        Ok(Some(code_page.decode(value)?.into_owned()))
+    }
+}
+
+impl Database {
+    /// Opens a database from a path, its memo file (DCT) is needed too
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut database = Self::from_table(DbfReader::open(path)?)?;
+        database.directory = path.parent().map(Path::to_path_buf);
+
+        Ok(database)
+    }
+
+    /// Reads a database from its table, it must have its memo attached
+    pub fn from_table<R: Read + Seek>(mut dbf: DbfReader<R>) -> Result<Self, Error> {
+        let code_page = dbf.code_page();
+
+        let mut objects = Vec::new();
+        // deleted objects are gone from the database
+        for row in dbf.rows().with_filter(RowFilter::Live) {
+            let row = row?;
+            objects.push(Object {
+                id: integer(&row, "OBJECTID")?,
+                parent: integer(&row, "PARENTID")?,
+                kind: text(&row, "OBJECTTYPE")?,
+                name: text(&row, "OBJECTNAME")?,
+                properties: read_properties(&row)?,
+            });
+        }
+
+        let mut tables = Vec::new();
+        for table in objects.iter().filter(|object| object.kind == "Table") {
+            let mut fields = Vec::new();
+            for field in objects
+                .iter()
+                .filter(|object| object.kind == "Field" && object.parent == table.id)
+            {
+                fields.push(DatabaseField {
+                    name: field.name.clone(),
+                    caption: field.property(CAPTION, code_page)?,
+                    default_value: field.property(DEFAULT_VALUE, code_page)?,
+                    rule: field.property(RULE_EXPRESSION, code_page)?,
+                    rule_text: field.property(RULE_TEXT, code_page)?,
+                });
+            }
+
+            tables.push(DatabaseTable {
+                name: table.name.clone(),
+                path: table.property(PATH, code_page)?,
+                rule: table.property(RULE_EXPRESSION, code_page)?,
+                rule_text: table.property(RULE_TEXT, code_page)?,
+                fields,
+            });
+        }
+
+        // relations belong to their child table
+        let mut relations = Vec::new();
+        for relation in objects.iter().filter(|object| object.kind == "Relation") {
+            let child_table = objects
+                .iter()
+                .find(|object| object.kind == "Table" && object.id == relation.parent)
+                .ok_or(FileFormat(format!(
+                    "relation without table: {}",
+                    relation.name
+                )))?;
+
+            relations.push(Relation {
+                child_table: child_table.name.clone(),
+                child_tag: relation.property(CHILD_TAG, code_page)?.unwrap_or_default(),
+                parent_table: relation
+                    .property(PARENT_TABLE, code_page)?
+                    .unwrap_or_default(),
+                parent_tag: relation
+                    .property(PARENT_TAG, code_page)?
+                    .unwrap_or_default(),
+            });
+        }
+
+        Ok(Self {
+            directory: None,
+            tables,
+            relations,
+        })
+    }
+
+    /// Tables in this database
+    pub fn tables(&self) -> &[DatabaseTable] {
+        &self.tables
+    }
+
+    /// Finds a table by its name, ignoring case like VFP does
+    pub fn table(&self, name: &str) -> Option<&DatabaseTable> {
+        self.tables
+            .iter()
+            .find(|table| table.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Persistent relations between tables in this database
+    pub fn relations(&self) -> &[Relation] {
+        &self.relations
+    }
+
+    /// Opens a table in this database, its fields get their long names
+    pub fn open_table(&self, name: &str) -> Result<DbfReader<File>, Error> {
+        let table = self.table(name).ok_or(TableNotFound(name.to_string()))?;
+
+        // paths are written by Windows
+        let path = match table.path() {
+            Some(path) => PathBuf::from(path.replace('\\', "/")),
+            None => PathBuf::from(format!("{}.dbf", table.name)),
+        };
+        let path = match &self.directory {
+            Some(directory) => directory.join(path),
+            None => path,
+        };
+
+        Ok(DbfReader::open(path)?.with_long_names(table))
+    }
+}
+
+fn text(row: &Row, column: &str) -> Result<String, Error> {
+    match row.get(column)? {
+        Value::Character(text) => Ok(text),
+        Value::Null => Ok(String::new()),
+        _ => Err(FileFormat(format!("invalid {column} in database"))),
+    }
+}
+
+fn integer(row: &Row, column: &str) -> Result<i32, Error> {
+    match row.get(column)? {
+        Value::Integer(value) => Ok(value),
+        _ => Err(FileFormat(format!("invalid {column} in database"))),
+    }
+}
+
+/// Splits the PROPERTY memo, each property has its length,
+/// a type we don't need, its id and a zero terminated value
+fn read_properties(row: &Row) -> Result<Vec<(u8, Vec<u8>)>, Error> {
+    let data = match row.get("PROPERTY")? {
+        Value::Binary(data) => data,
+        Value::Memo(text) => text.into_bytes(),
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut properties = Vec::new();
+    let mut rest = &data[..];
+    while rest.len() >= PROPERTY_HEADER {
+        let length = (&rest[..4]).read_u32::<LittleEndian>()? as usize;
+        if length < PROPERTY_HEADER || length > rest.len() {
+            return Err(FileFormat(format!(
+                "invalid database property length: {length}"
+            )));
+        }
+
+        let id = rest[PROPERTY_HEADER - 1];
+        let value = rest[PROPERTY_HEADER..length].until_terminator(&[0]);
+        properties.push((id, value.to_vec()));
+
+        rest = &rest[length..];
+    }
+
+    Ok(properties)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dbf::database::{
+        CAPTION, DEFAULT_VALUE, Database, DatabaseField, PATH, RULE_EXPRESSION, RULE_TEXT, Relation,
+    };
+    use crate::dbf::fixtures::TableBuilder;
+    use crate::dbf::reader::DbfReader;
+    use crate::errors::Error::TableNotFound;
+    use crate::memo::fpt::FptReader;
+    use std::io::Cursor;
+
+    const BLOCK_SIZE: usize = 64;
+
+    fn property(id: u8, value: &str) -> Vec<u8> {
+        let mut data = ((value.len() + 8) as u32).to_le_bytes().to_vec();
+        data.extend([0x01, 0x00, id]);
+        data.extend(value.as_bytes());
+        data.push(0x00);
+        data
+    }
+
+    /// Builds a database with its memo, objects are (id, parent, type, name, properties)
+    fn database(objects: &[(i32, i32, &str, &str, Vec<u8>)]) -> (Vec<u8>, Vec<u8>) {
+        // a shorter name than the 128 characters VFP uses
+        let mut dbc = TableBuilder::new(0x30)
+            .with_last_update(124, 1, 1)
+            .with_flags(0x06)
+            .with_language_driver(0x03)
+            .with_field("OBJECTID", b'I', 4)
+            .with_field("PARENTID", b'I', 4)
+            .with_field("OBJECTTYPE", b'C', 10)
+            .with_field("OBJECTNAME", b'C', 20)
+            .with_flagged_field("PROPERTY", b'M', 4, 0x04);
+
+        let mut dct = vec![0u8; 512];
+        for (id, parent, kind, name, properties) in objects {
+            let mut record = vec![0x20];
+            record.extend(id.to_le_bytes());
+            record.extend(parent.to_le_bytes());
+            record.extend(format!("{kind:<10}").as_bytes());
+            record.extend(format!("{name:<20}").as_bytes());
+
+            if properties.is_empty() {
+                record.extend(0u32.to_le_bytes());
+                dbc = dbc.with_record(&record);
+                continue;
+            }
+
+            record.extend(((dct.len() / BLOCK_SIZE) as u32).to_le_bytes());
+            dbc = dbc.with_record(&record);
+            dct.extend(2u32.to_be_bytes());
+            dct.extend((properties.len() as u32).to_be_bytes());
+            dct.extend(properties);
+            dct.resize(dct.len().div_ceil(BLOCK_SIZE) * BLOCK_SIZE, 0x00);
+        }
+
+        let next_block = (dct.len() / BLOCK_SIZE) as u32;
+        dct[..4].copy_from_slice(&next_block.to_be_bytes());
+        dct[6..8].copy_from_slice(&(BLOCK_SIZE as u16).to_be_bytes());
+
+        (dbc.build(), dct)
+    }
+
+    /// The database and memo for customers and orders
+    fn sales_files() -> (Vec<u8>, Vec<u8>) {
+        let customer_id = [
+            property(CAPTION, "Customer"),
+            property(DEFAULT_VALUE, "0"),
+            property(RULE_EXPRESSION, "customer_id > 0"),
+            property(RULE_TEXT, "Must be positive"),
+        ]
+        .concat();
+        // ChildTag (0x0d), ParentTable (0x12) and ParentTag (0x13) as VFP writes them
+        let relation = [
+            &[0x10, 0x00, 0x00, 0x00, 0x01, 0x00, 0x0d][..],
+            b"customer\0",
+            &[0x11, 0x00, 0x00, 0x00, 0x01, 0x00, 0x12],
+            b"customers\0",
+            &[0x0a, 0x00, 0x00, 0x00, 0x01, 0x00, 0x13],
+            b"id\0",
+        ]
+        .concat();
+
+        database(&[
+            (1, 1, "Database", "Database", vec![]),
+            (2, 1, "Table", "customers", property(PATH, "customers.dbf")),
+            (3, 2, "Field", "customer_id", customer_id),
+            (4, 2, "Field", "customer_full_name", vec![]),
+            (5, 1, "Table", "orders", property(PATH, "data\\orders.dbf")),
+            (6, 5, "Field", "order_id", vec![]),
+            (7, 5, "Field", "customer_id", vec![]),
+            (8, 5, "Relation", "Relation 1", relation),
+        ])
+    }
+
+    fn sales() -> anyhow::Result<Database> {
+        let (dbc, dct) = sales_files();
+        let dbf = DbfReader::from_reader(Cursor::new(dbc))?;
+        let dbf = dbf.with_memo(FptReader::from_reader(Cursor::new(dct))?);
+
+        Ok(Database::from_table(dbf)?)
+    }
+
+    #[test]
+    fn read_database_tables() -> anyhow::Result<()> {
+        let database = sales()?;
+
+        let names: Vec<_> = database.tables().iter().map(|table| table.name()).collect();
+        assert_eq!(vec!["customers", "orders"], names);
+
+        let customers = database.table("CUSTOMERS").expect("customers table");
+        assert_eq!(Some("customers.dbf"), customers.path());
+        let expected = vec![
+            DatabaseField {
+                name: "customer_id".to_string(),
+                caption: Some("Customer".to_string()),
+                default_value: Some("0".to_string()),
+                rule: Some("customer_id > 0".to_string()),
+                rule_text: Some("Must be positive".to_string()),
+            },
+            DatabaseField {
+                name: "customer_full_name".to_string(),
+                ..DatabaseField::default()
+            },
+        ];
+        assert_eq!(expected, customers.fields());
+
+        let orders = database.table("orders").expect("orders table");
+        assert_eq!(Some("data\\orders.dbf"), orders.path());
+        assert_eq!(2, orders.fields().len());
+
+        assert!(database.table("invoices").is_none());
+
+        Ok(())
+    }
+
+    /// A VFP table without records, its character fields are 10 bytes long
+    fn table(names: &[&str]) -> Vec<u8> {
+        let mut table = TableBuilder::new(0x30).with_last_update(124, 1, 1);
+        for name in names {
+            table = table.with_field(name, b'C', 10);
+        }
+
+        table.build()
+    }
+
+    #[test]
+    fn open_database_tables() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("dollop-dbc-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("data"))?;
+
+        // DOS didn't care about case, the memo is found anyway
+        let (dbc, dct) = sales_files();
+        std::fs::write(dir.join("SALES.DBC"), dbc)?;
+        std::fs::write(dir.join("Sales.Dct"), dct)?;
+        std::fs::write(
+            dir.join("customers.dbf"),
+            table(&["CUSTOMER_I", "CUSTOMER_F"]),
+        )?;
+        std::fs::write(
+            dir.join("data").join("orders.dbf"),
+            table(&["ORDER_ID", "CUSTOMER_I"]),
+        )?;
+
+        let opened = (|| -> anyhow::Result<_> {
+            let database = Database::open(dir.join("SALES.DBC"))?;
+            let customers = database.open_table("Customers")?;
+            let orders = database.open_table("orders")?;
+            let invoices = database.open_table("invoices");
+            Ok((customers, orders, invoices))
+        })();
+        std::fs::remove_dir_all(&dir)?;
+        let (customers, orders, invoices) = opened?;
+
+        let names: Vec<_> = customers
+            .fields()
+            .iter()
+            .map(|field| field.name())
+            .collect();
+        assert_eq!(vec!["customer_id", "customer_full_name"], names);
+
+        // paths written by Windows are relative to the database
+        let names: Vec<_> = orders.fields().iter().map(|field| field.name()).collect();
+        assert_eq!(vec!["order_id", "customer_id"], names);
+
+        assert!(matches!(invoices, Err(TableNotFound(name)) if name == "invoices"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_database_relations() -> anyhow::Result<()> {
+        let expected = vec![Relation {
+            child_table: "orders".to_string(),
+            child_tag: "customer".to_string(),
+            parent_table: "customers".to_string(),
+            parent_tag: "id".to_string(),
+        }];
+        assert_eq!(expected, sales()?.relations());
+
+        Ok(())
+    }
+}
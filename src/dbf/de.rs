@@ -0,0 +1,503 @@
+use crate::dbf::convert::in_column;
+use crate::dbf::rows::{ColumnIndex, RowRef, Value};
+use crate::errors::Error;
+use crate::errors::Error::Deserialize;
+use rust_decimal::prelude::ToPrimitive;
+use serde::de::value::SeqDeserializer;
+use serde::de::{DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+
+impl<'de, 'a> Deserializer<'de> for RowRef<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let columns = self
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| (field.name(), ColumnIndex(index)))
+            .collect();
+
+        visitor.visit_map(RowAccess {
+            row: self,
+            columns,
+            next: 0,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        // struct fields get the column with the same name, ignoring case,
+        // those without a column are missing for serde to complain or not
+        let columns = fields
+            .iter()
+            .filter_map(|name| Some((*name, self.column(name).ok()?)))
+            .collect();
+
+        visitor.visit_map(RowAccess {
+            row: self,
+            columns,
+            next: 0,
+        })
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(RowSeq { row: self, next: 0 })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    /// Values are handed out in their compact forms, see `ValueDeserializer`
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct enum identifier ignored_any
+    }
+}
+
+/// Columns of a row as the entries of a map
+struct RowAccess<'a> {
+    row: RowRef<'a>,
+    columns: Vec<(&'a str, ColumnIndex)>,
+    next: usize,
+}
+
+impl<'de, 'a> MapAccess<'de> for RowAccess<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some((name, _)) = self.columns.get(self.next) else {
+            return Ok(None);
+        };
+
+        seed.deserialize((*name).into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let (name, column) = self.columns[self.next];
+        self.next += 1;
+
+        let value = self.row.get_by_handle(column)?;
+        seed.deserialize(ValueDeserializer(value))
+            .map_err(|error| in_column(name, error))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.columns.len() - self.next)
+    }
+}
+
+/// Columns of a row in the same order as the fields
+struct RowSeq<'a> {
+    row: RowRef<'a>,
+    next: usize,
+}
+
+impl<'de, 'a> SeqAccess<'de> for RowSeq<'a> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        let Some(field) = self.row.fields().get(self.next) else {
+            return Ok(None);
+        };
+
+        let value = self.row.get_by_index(self.next)?;
+        self.next += 1;
+
+        seed.deserialize(ValueDeserializer(value))
+            .map(Some)
+            .map_err(|error| in_column(field.name(), error))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.row.fields().len() - self.next)
+    }
+}
+
+/// Deserializes a single value, converting between numbers when nothing is lost.
+///
+/// `time` reads dates from the compact tuples of its own serde impls, as we
+/// are not human readable, so `deserialize_tuple` must keep building them the
+/// same way. Its `with` modules work too: dates are strings in the
+/// `[year]-[month]-[day]` format, date times in the
+/// `[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:3]` one,
+/// and both are `i64` unix timestamps (taken as UTC) for `time::serde::timestamp`.
+/// That is the only integer dates turn into, so an `i64` field reading a date
+/// column gets its timestamp while narrower integers refuse it
+struct ValueDeserializer(Value);
+
+impl ValueDeserializer {
+    fn integer(&self) -> Result<i64, Error> {
+        let integer = match &self.0 {
+            Value::Integer(value) => Some(*value as i64),
+            Value::Numeric(value) | Value::Currency(value) if value.fract().is_zero() => {
+                value.to_i64()
+            }
+            Value::Float(value) | Value::Double(value) if value.fract() == 0.0 => {
+                Some(*value as i64)
+            }
+            _ => None,
+        };
+
+        integer.ok_or(Deserialize(format!(
+            "expected an integer, found {:?}",
+            self.0
+        )))
+    }
+
+    fn out_of_range(&self, kind: &str) -> Error {
+        Deserialize(format!("expected {kind}, found {:?}", self.0))
+    }
+
+    fn float(&self) -> Result<f64, Error> {
+        let float = match &self.0 {
+            Value::Float(value) | Value::Double(value) => Some(*value),
+            Value::Numeric(value) | Value::Currency(value) => value.to_f64(),
+            Value::Integer(value) => Some(*value as f64),
+            _ => None,
+        };
+
+        float.ok_or(Deserialize(format!(
+            "expected a number, found {:?}",
+            self.0
+        )))
+    }
+}
+
+macro_rules! deserialize_integer {
+    ($($method:ident => $integer:ident $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                let integer = $integer::try_from(self.integer()?)
+                    .map_err(|_| self.out_of_range(concat!("a ", stringify!($integer))))?;
+                visitor.$visit(integer)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Character(text) | Value::Memo(text) => visitor.visit_string(text),
+            // as text so decimals keep their scale
+            Value::Numeric(value) | Value::Currency(value) => {
+                visitor.visit_string(value.to_string())
+            }
+            Value::Float(value) | Value::Double(value) => visitor.visit_f64(value),
+            Value::Integer(value) => visitor.visit_i32(value),
+            Value::Logical(value) => visitor.visit_bool(value),
+            Value::Date(date) => visitor.visit_string(date.to_string()),
+            Value::DateTime(date_time) => visitor.visit_string(format!(
+                "{} {:02}:{:02}:{:02}.{:03}",
+                date_time.date(),
+                date_time.hour(),
+                date_time.minute(),
+                date_time.second(),
+                date_time.millisecond()
+            )),
+            Value::Binary(data) => visitor.visit_byte_buf(data),
+            Value::Null => visitor.visit_none(),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    deserialize_integer! {
+        deserialize_i8 => i8 visit_i8,
+        deserialize_i16 => i16 visit_i16,
+        deserialize_i32 => i32 visit_i32,
+        deserialize_u8 => u8 visit_u8,
+        deserialize_u16 => u16 visit_u16,
+        deserialize_u32 => u32 visit_u32,
+        deserialize_u64 => u64 visit_u64,
+    }
+
+    /// `time::serde::timestamp` reads an `i64`, so dates are timestamps here
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let integer = match &self.0 {
+            Value::Date(date) => date.midnight().assume_utc().unix_timestamp(),
+            Value::DateTime(date_time) => date_time.assume_utc().unix_timestamp(),
+            _ => self.integer()?,
+        };
+        visitor.visit_i64(integer)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(self.float()?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(self.float()?)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    /// `time` reads dates as `(year, ordinal)` and date times as
+    /// `(year, ordinal, hour, minute, second, nanosecond)` tuples
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Date(date) => {
+                let parts = [date.year(), date.ordinal() as i32];
+                visitor.visit_seq(SeqDeserializer::new(parts.into_iter()))
+            }
+            Value::DateTime(date_time) => {
+                let parts = [
+                    date_time.year(),
+                    date_time.ordinal() as i32,
+                    date_time.hour() as i32,
+                    date_time.minute() as i32,
+                    date_time.second() as i32,
+                    date_time.nanosecond() as i32,
+                ];
+                visitor.visit_seq(SeqDeserializer::new(parts.into_iter()))
+            }
+            value => ValueDeserializer(value).deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i128 u128 char str string bytes byte_buf unit unit_struct
+        newtype_struct seq tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dbf::reader::DbfReader;
+    use crate::errors::Error;
+    use crate::sample_file;
+    use rust_decimal::Decimal;
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+    use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Product {
+        id: i32,
+        name: String,
+        price: Decimal,
+        #[serde(rename = "COST")]
+        unit_cost: Decimal,
+        qty: u32,
+        weight: f64,
+        active: bool,
+        added: Date,
+        updated: PrimitiveDateTime,
+        discontinued: Option<bool>,
+    }
+
+    #[test]
+    fn deserialize_structs() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::from_reader(sample_file("vfp.dbf")?)?;
+        let products = dbf
+            .rows()
+            .map(|row| Ok(row?.deserialize::<Product>()?))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let added = Date::from_calendar_date(1925, Month::January, 15)?;
+        assert_eq!(7, products.len());
+        assert_eq!(
+            Product {
+                id: 1,
+                name: "Widget Pro".to_string(),
+                price: Decimal::new(2999, 2),
+                unit_cost: Decimal::new(155000, 4),
+                qty: 150,
+                weight: 0.35,
+                active: true,
+                added,
+                updated: PrimitiveDateTime::new(added, Time::from_hms(10, 30, 0)?),
+                discontinued: None,
+            },
+            products[0]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_nulls() -> anyhow::Result<()> {
+        #[derive(Deserialize)]
+        struct Entry {
+            name: Option<String>,
+            added: Option<Date>,
+        }
+
+        let mut dbf = DbfReader::from_reader(sample_file("db3.dbf")?)?;
+
+        let first: Entry = dbf.record(0)?.deserialize()?;
+        assert_eq!(Some("Widget Pro"), first.name.as_deref());
+        assert_eq!(
+            Some(Date::from_calendar_date(1925, Month::January, 15)?),
+            first.added
+        );
+
+        // the last record is blank
+        let last: Entry = dbf.record(7)?.deserialize()?;
+        assert_eq!(None, last.name);
+        assert_eq!(None, last.added);
+
+        // which is only fine for options
+        assert!(dbf.record(7)?.deserialize::<(String,)>().is_err());
+        Ok(())
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(untagged)]
+    enum Cell {
+        Text(String),
+        Flag(bool),
+    }
+
+    #[test]
+    fn deserialize_tuples_and_maps() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::from_reader(sample_file("db3.dbf")?)?;
+        let row = dbf.rows().next().unwrap()?;
+
+        let (name, price, qty): (String, Decimal, i64) = row.deserialize()?;
+        assert_eq!("Widget Pro", name);
+        assert_eq!(Decimal::new(2999, 2), price);
+        assert_eq!(150, qty);
+
+        let map: BTreeMap<String, Cell> = row.deserialize()?;
+        assert_eq!(Cell::Text("Widget Pro".to_string()), map["NAME"]);
+        assert_eq!(Cell::Text("29.99".to_string()), map["PRICE"]);
+        assert_eq!(Cell::Flag(true), map["ACTIVE"]);
+        Ok(())
+    }
+
+    time::serde::format_description!(day, Date, "[year]-[month]-[day]");
+    time::serde::format_description!(
+        moment,
+        PrimitiveDateTime,
+        "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:3]"
+    );
+
+    #[test]
+    fn deserialize_time_formats() -> anyhow::Result<()> {
+        #[derive(Deserialize)]
+        struct Dates {
+            #[serde(with = "day")]
+            added: Date,
+            #[serde(rename = "ADDED", with = "time::serde::timestamp")]
+            added_at: OffsetDateTime,
+            #[serde(with = "moment")]
+            updated: PrimitiveDateTime,
+            #[serde(rename = "UPDATED", with = "time::serde::timestamp::option")]
+            updated_at: Option<OffsetDateTime>,
+        }
+
+        let mut dbf = DbfReader::from_reader(sample_file("vfp.dbf")?)?;
+        let dates: Dates = dbf.rows().next().unwrap()?.deserialize()?;
+
+        let added = Date::from_calendar_date(1925, Month::January, 15)?;
+        let updated = PrimitiveDateTime::new(added, Time::from_hms(10, 30, 0)?);
+        assert_eq!(added, dates.added);
+        assert_eq!(added.midnight().assume_utc(), dates.added_at);
+        assert_eq!(updated, dates.updated);
+        assert_eq!(Some(updated.assume_utc()), dates.updated_at);
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_errors_name_the_column() -> anyhow::Result<()> {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Wrong {
+            price: u8,
+        }
+
+        let mut dbf = DbfReader::from_reader(sample_file("db3.dbf")?)?;
+        let row = dbf.rows().next().unwrap()?;
+
+        let Err(Error::Deserialize(message)) = row.deserialize::<Wrong>() else {
+            panic!("price is not an integer");
+        };
+        assert!(
+            message.starts_with("price: expected an integer"),
+            "{message}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_integers_in_range() -> anyhow::Result<()> {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Stock {
+            qty: u8,
+        }
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Added {
+            added: i32,
+        }
+
+        let mut dbf = DbfReader::from_reader(sample_file("db3.dbf")?)?;
+        assert_eq!(150, dbf.record(0)?.deserialize::<Stock>()?.qty);
+
+        let Err(Error::Deserialize(message)) = dbf.record(1)?.deserialize::<Stock>() else {
+            panic!("500 doesn't fit in a u8");
+        };
+        assert!(
+            message.starts_with("qty: expected a u8, found "),
+            "{message}"
+        );
+
+        // only time's timestamps read dates as integers
+        let Err(Error::Deserialize(message)) = dbf.record(0)?.deserialize::<Added>() else {
+            panic!("a date is not an integer");
+        };
+        assert!(
+            message.starts_with("added: expected an integer"),
+            "{message}"
+        );
+        Ok(())
+    }
+}
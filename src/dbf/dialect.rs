@@ -0,0 +1,289 @@
+use crate::dbf::header::{DbfVersion, Field, FieldType, Header, MemoLayout};
+use crate::errors::Error;
+use crate::memo::read_header;
+use std::io::{Read, Seek, SeekFrom};
+use strum::Display;
+
+/// The program (family) that most likely wrote a table
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display)]
+pub enum Dialect {
+    /// dBase II and the first FoxBASE, which copied its layout
+    #[strum(to_string = "dBase II")]
+    Dbase2,
+    #[strum(to_string = "dBase III")]
+    Dbase3,
+    /// dBase IV and 5 write the same tables
+    #[strum(to_string = "dBase IV")]
+    Dbase4,
+    /// FoxBASE+ and FoxPro 1.x and 2.x
+    #[strum(to_string = "FoxPro")]
+    FoxPro,
+    #[strum(to_string = "Visual FoxPro")]
+    VisualFoxPro,
+    #[strum(to_string = "dBase 7")]
+    Dbase7,
+    #[strum(to_string = "Clipper")]
+    Clipper,
+}
+
+/// How much we trust a dialect guess
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Display)]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+/// A guess of the dialect for a table and how sure we are about it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DialectGuess {
+    pub dialect: Dialect,
+    pub confidence: Confidence,
+}
+
+impl DialectGuess {
+    fn new(dialect: Dialect, confidence: Confidence) -> Self {
+        Self {
+            dialect,
+            confidence,
+        }
+    }
+}
+
+/// What the header of the memo file tells us about who wrote it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MemoEvidence {
+    /// dBase IV writes its block size at offset 20,
+    /// dBase III and FoxBASE+ leave it zeroed
+    Dbt { block_size: u16 },
+    /// FoxPro writes its block size at offset 6
+    Fpt { block_size: u16 },
+}
+
+impl MemoEvidence {
+    /// Reads the evidence at the start of a memo file, `reader` is left at 0
+    pub(crate) fn from_reader<R: Read + Seek>(
+        layout: MemoLayout,
+        reader: &mut R,
+    ) -> Result<Option<Self>, Error> {
+        let start = read_header(reader)?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        let evidence = match layout {
+            MemoLayout::Dbt3 | MemoLayout::Dbt4 => Some(Self::Dbt {
+                block_size: u16::from_le_bytes([start[20], start[21]]),
+            }),
+            MemoLayout::Fpt => Some(Self::Fpt {
+                block_size: u16::from_be_bytes([start[6], start[7]]),
+            }),
+            MemoLayout::Smt => None,
+        };
+
+        Ok(evidence)
+    }
+}
+
+/// Tells us if the memo file is laid out the way the version byte says,
+/// without a memo file there is nothing to disagree with
+fn memo_agrees(version: DbfVersion, memo: Option<MemoEvidence>) -> bool {
+    match (version.memo_layout(), memo) {
+        (_, None) => true,
+        (Some(MemoLayout::Dbt3), Some(MemoEvidence::Dbt { block_size })) => block_size == 0,
+        (Some(MemoLayout::Dbt4), Some(MemoEvidence::Dbt { block_size })) => block_size != 0,
+        (Some(MemoLayout::Fpt), Some(MemoEvidence::Fpt { block_size })) => block_size != 0,
+        _ => false,
+    }
+}
+
+/// Guesses the dialect from the evidence left in the header, fields and memo file
+///
+/// Most version bytes were only written by one family, but 0x03 and 0x83
+/// were written by dBase III, dBase IV, FoxBASE+ and FoxPro alike so for
+/// them we need to look around, and we are never sure about dBase III
+pub(crate) fn guess(header: &Header, fields: &[Field], memo: Option<MemoEvidence>) -> DialectGuess {
+    // the version byte is only trusted when its memo file agrees
+    let sure = if memo_agrees(header.version, memo) {
+        Confidence::High
+    } else {
+        Confidence::Medium
+    };
+
+    match header.version {
+        DbfVersion::Dbase4WithMemo
+        | DbfVersion::Dbase4SqlTable
+        | DbfVersion::Dbase4SqlSystem
+        | DbfVersion::Dbase4SqlSystemWithMemo
+        | DbfVersion::Dbase4SqlTableWithMemo => return DialectGuess::new(Dialect::Dbase4, sure),
+        // we only read 0x02 tables laid out the dBase II way
+        DbfVersion::FoxBase => return DialectGuess::new(Dialect::Dbase2, Confidence::High),
+        DbfVersion::FoxBaseWithMemo | DbfVersion::FoxProWithMemo => {
+            return DialectGuess::new(Dialect::FoxPro, sure);
+        }
+        DbfVersion::VisualFoxPro
+        | DbfVersion::VisualFoxProAutoincrement
+        | DbfVersion::VisualFoxProVarchar => {
+            return DialectGuess::new(Dialect::VisualFoxPro, sure);
+        }
+        DbfVersion::Dbase7 | DbfVersion::Dbase7WithMemo => {
+            return DialectGuess::new(Dialect::Dbase7, sure);
+        }
+        DbfVersion::ClipperSix => return DialectGuess::new(Dialect::Clipper, Confidence::High),
+        DbfVersion::Dbase | DbfVersion::Dbase3WithMemo => {}
+    }
+
+    // FoxPro writes where every field starts, dBase leaves garbage there
+    let foxpro_layout = !fields.is_empty()
+        && fields
+            .iter()
+            .all(|field| field.displacement == field.offset as u32);
+    if foxpro_layout {
+        return DialectGuess::new(Dialect::FoxPro, Confidence::High);
+    }
+
+    // dBase IV got the year right after 1999, but so did Clipper, Harbour
+    // and most programs still writing these tables
+    let y2k_ready = header.last_update.year() >= 2000;
+
+    // dBase III didn't know floats, language drivers nor production indices,
+    // it left the reserved bytes and the DBT block size zeroed too
+    let has_float = fields
+        .iter()
+        .any(|field| matches!(field.field_type, FieldType::Float { .. }));
+    let has_reserved = header.reserved.iter().any(|byte| *byte != 0);
+    let dbt4 = matches!(memo, Some(MemoEvidence::Dbt { block_size }) if block_size != 0);
+    if has_float
+        || has_reserved
+        || dbt4
+        || header.language_driver != 0
+        || header.has_production_index()
+    {
+        let confidence = if y2k_ready {
+            Confidence::High
+        } else {
+            Confidence::Medium
+        };
+        return DialectGuess::new(Dialect::Dbase4, confidence);
+    }
+    if y2k_ready {
+        return DialectGuess::new(Dialect::Dbase4, Confidence::Low);
+    }
+
+    // dBase III and FoxBASE+ write the same tables, but a year before 1980
+    // tells us it was written after 1999 by someone that didn't know better
+    // and a DBT laid out the dBase III way backs it up
+    let y2k_broken = header.last_update.year() < 1980;
+    let dbt3 = matches!(memo, Some(MemoEvidence::Dbt { block_size: 0 }));
+    if y2k_broken || dbt3 {
+        DialectGuess::new(Dialect::Dbase3, Confidence::Medium)
+    } else {
+        DialectGuess::new(Dialect::Dbase3, Confidence::Low)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dbf::dialect::{Confidence, Dialect, DialectGuess, MemoEvidence};
+    use crate::dbf::reader::DbfReader;
+    use crate::{sample_file, sample_path};
+    use std::io::{Cursor, Read};
+
+    fn guess(name: &str) -> anyhow::Result<DialectGuess> {
+        Ok(DbfReader::open(sample_path(name))?.dialect())
+    }
+
+    /// Guesses a sample table after changing some of its header bytes
+    fn guess_patched(name: &str, patch: &[(usize, u8)]) -> anyhow::Result<DialectGuess> {
+        let mut data = vec![];
+        sample_file(name)?.read_to_end(&mut data)?;
+        for (position, byte) in patch {
+            data[*position] = *byte;
+        }
+        Ok(DbfReader::from_reader(Cursor::new(data))?.dialect())
+    }
+
+    #[test]
+    fn guess_tables_without_memo() -> anyhow::Result<()> {
+        let expected = DialectGuess::new(Dialect::Dbase3, Confidence::Medium);
+        assert_eq!(expected, guess("db3.dbf")?);
+
+        let expected = DialectGuess::new(Dialect::Dbase4, Confidence::High);
+        assert_eq!(expected, guess("db4.dbf")?);
+        assert_eq!(expected, guess("db5.dbf")?);
+
+        let expected = DialectGuess::new(Dialect::FoxPro, Confidence::High);
+        assert_eq!(expected, guess("fox1.dbf")?);
+        assert_eq!(expected, guess("fox2.dbf")?);
+
+        let expected = DialectGuess::new(Dialect::VisualFoxPro, Confidence::High);
+        assert_eq!(expected, guess("vfp.dbf")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn guess_tables_with_memo() -> anyhow::Result<()> {
+        // FoxBASE+ and FoxPro write 0x83 tables too
+        let expected = DialectGuess::new(Dialect::Dbase3, Confidence::Medium);
+        assert_eq!(expected, guess("db3memo.dbf")?);
+
+        let expected = DialectGuess::new(Dialect::Dbase4, Confidence::High);
+        assert_eq!(expected, guess("db4memo.dbf")?);
+        assert_eq!(expected, guess("db5memo.dbf")?);
+
+        let expected = DialectGuess::new(Dialect::FoxPro, Confidence::High);
+        assert_eq!(expected, guess("fox1memo.dbf")?);
+        assert_eq!(expected, guess("fox2memo.dbf")?);
+
+        let expected = DialectGuess::new(Dialect::VisualFoxPro, Confidence::High);
+        assert_eq!(expected, guess("vfpmemo.dbf")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn guess_from_memo_headers() -> anyhow::Result<()> {
+        let dbf = DbfReader::from_reader(sample_file("db3memo.dbf")?)?;
+        let (header, fields) = (dbf.header(), dbf.all_fields());
+
+        // without a memo file only the year broken after 1999 points at dBase III
+        let expected = DialectGuess::new(Dialect::Dbase3, Confidence::Medium);
+        assert_eq!(expected, super::guess(header, fields, None));
+
+        // only dBase IV writes its block size in DBT files
+        let memo = MemoEvidence::Dbt { block_size: 512 };
+        let expected = DialectGuess::new(Dialect::Dbase4, Confidence::Medium);
+        assert_eq!(expected, super::guess(header, fields, Some(memo)));
+
+        // a DBT without block size for a dBase IV table makes us doubt it
+        let dbf = DbfReader::from_reader(sample_file("db4memo.dbf")?)?;
+        let memo = MemoEvidence::Dbt { block_size: 0 };
+        let expected = DialectGuess::new(Dialect::Dbase4, Confidence::Medium);
+        assert_eq!(
+            expected,
+            super::guess(dbf.header(), dbf.all_fields(), Some(memo))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn guess_from_header_bytes() -> anyhow::Result<()> {
+        // dBase III left the multi-user bytes zeroed
+        let expected = DialectGuess::new(Dialect::Dbase4, Confidence::Medium);
+        assert_eq!(expected, guess_patched("db3.dbf", &[(16, 0x01)])?);
+
+        // a year before 2000 could be right, so it tells us nothing
+        let expected = DialectGuess::new(Dialect::Dbase3, Confidence::Low);
+        assert_eq!(expected, guess_patched("db3.dbf", &[(1, 95)])?);
+
+        // a year after 1999 is written by dBase IV, but by Clipper too
+        let expected = DialectGuess::new(Dialect::Dbase4, Confidence::Low);
+        assert_eq!(expected, guess_patched("db3.dbf", &[(1, 105)])?);
+
+        // along with the multi-user bytes it is dBase IV
+        let expected = DialectGuess::new(Dialect::Dbase4, Confidence::High);
+        assert_eq!(expected, guess_patched("db3.dbf", &[(1, 105), (16, 0x01)])?);
+
+        Ok(())
+    }
+}
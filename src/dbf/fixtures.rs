@@ -0,0 +1,237 @@
+//! Tables built byte by byte for tests, for the layouts the samples don't cover
+
+/// VFP keeps room for the path to its database after the fields
+const BACKLINK_SIZE: usize = 263;
+
+/// dBase II leaves room for 32 descriptors before the records
+const LEVEL2_HEADER_SIZE: usize = 521;
+
+struct FixtureField {
+    name: String,
+    field_type: u8,
+    length: u16,
+    decimal: u8,
+    flags: u8,
+}
+
+/// Builds a table laid out the way its version byte says, the header
+/// sizes, record length and field offsets are worked out from its fields
+pub(crate) struct TableBuilder {
+    version: u8,
+    last_update: (u8, u8, u8),
+    flags: u8,
+    language_driver: u8,
+    language_driver_name: String,
+    fields: Vec<FixtureField>,
+    properties: Vec<u8>,
+    backlink: String,
+    records: Vec<Vec<u8>>,
+}
+
+impl TableBuilder {
+    /// An empty table last updated on 2026-02-17
+    pub(crate) fn new(version: u8) -> Self {
+        Self {
+            version,
+            last_update: (126, 2, 17),
+            flags: 0,
+            language_driver: 0,
+            language_driver_name: String::new(),
+            fields: Vec::new(),
+            properties: Vec::new(),
+            backlink: String::new(),
+            records: Vec::new(),
+        }
+    }
+
+    /// Sets the date of the last update, the year is counted from 1900
+    pub(crate) fn with_last_update(mut self, year: u8, month: u8, day: u8) -> Self {
+        self.last_update = (year, month, day);
+        self
+    }
+
+    /// Sets the table flags (byte 28)
+    pub(crate) fn with_flags(mut self, flags: u8) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Sets the language driver ID (byte 29)
+    pub(crate) fn with_language_driver(mut self, id: u8) -> Self {
+        self.language_driver = id;
+        self
+    }
+
+    /// Sets the language driver name, only dBase 7 has it
+    pub(crate) fn with_language_driver_name(mut self, name: &str) -> Self {
+        self.language_driver_name = name.to_string();
+        self
+    }
+
+    pub(crate) fn with_field(self, name: &str, field_type: u8, length: u16) -> Self {
+        self.with_full_field(name, field_type, length, 0, 0)
+    }
+
+    pub(crate) fn with_numeric_field(self, name: &str, length: u16, decimal: u8) -> Self {
+        self.with_full_field(name, b'N', length, decimal, 0)
+    }
+
+    /// Adds a field with VFP flags (system, nullable, binary...)
+    pub(crate) fn with_flagged_field(
+        self,
+        name: &str,
+        field_type: u8,
+        length: u16,
+        flags: u8,
+    ) -> Self {
+        self.with_full_field(name, field_type, length, 0, flags)
+    }
+
+    fn with_full_field(
+        mut self,
+        name: &str,
+        field_type: u8,
+        length: u16,
+        decimal: u8,
+        flags: u8,
+    ) -> Self {
+        self.fields.push(FixtureField {
+            name: name.to_string(),
+            field_type,
+            length,
+            decimal,
+            flags,
+        });
+        self
+    }
+
+    /// Sets the field properties written after the fields, only dBase 7 has them
+    pub(crate) fn with_properties(mut self, properties: &[u8]) -> Self {
+        self.properties = properties.to_vec();
+        self
+    }
+
+    /// Sets the path to the database, only VFP has it
+    pub(crate) fn with_backlink(mut self, path: &str) -> Self {
+        self.backlink = path.to_string();
+        self
+    }
+
+    /// Adds a record, starting with its deleted flag
+    pub(crate) fn with_record(mut self, record: &[u8]) -> Self {
+        self.records.push(record.to_vec());
+        self
+    }
+
+    pub(crate) fn build(&self) -> Vec<u8> {
+        let record_length = 1 + self.fields.iter().map(|field| field.length).sum::<u16>();
+
+        let mut data = match self.version {
+            0x02 => self.level2_header(record_length),
+            0x04 | 0x8c => self.level7_header(record_length),
+            _ => self.header(record_length),
+        };
+        for record in &self.records {
+            assert_eq!(record_length as usize, record.len(), "record length");
+            data.extend(record);
+        }
+        data.push(0x1a);
+
+        data
+    }
+
+    /// The layout most versions share, since dBase III
+    fn header(&self, record_length: u16) -> Vec<u8> {
+        let visual_foxpro = matches!(self.version, 0x30..=0x32);
+        let backlink = if visual_foxpro { BACKLINK_SIZE } else { 0 };
+        let header_size = 32 + 32 * self.fields.len() + 1 + backlink;
+
+        let (year, month, day) = self.last_update;
+        let mut data = vec![self.version, year, month, day];
+        data.extend((self.records.len() as u32).to_le_bytes());
+        data.extend((header_size as u16).to_le_bytes());
+        data.extend(record_length.to_le_bytes());
+        data.resize(28, 0x00);
+        data.extend([self.flags, self.language_driver]);
+        data.resize(32, 0x00);
+
+        // FoxPro writes where fields start, dBase leaves it zeroed
+        let mut offset = 1u32;
+        for field in &self.fields {
+            let mut descriptor = field.name.as_bytes().to_vec();
+            descriptor.resize(11, 0x00);
+            descriptor.push(field.field_type);
+            let displacement = if visual_foxpro { offset } else { 0 };
+            descriptor.extend(displacement.to_le_bytes());
+            // Clipper keeps long character lengths in the decimal byte
+            if field.length > 255 {
+                descriptor.extend(field.length.to_le_bytes());
+            } else {
+                descriptor.extend([field.length as u8, field.decimal]);
+            }
+            descriptor.push(field.flags);
+            descriptor.resize(32, 0x00);
+            data.extend(descriptor);
+            offset += u32::from(field.length);
+        }
+        data.push(0x0d);
+
+        let mut backlink = self.backlink.as_bytes().to_vec();
+        backlink.resize(backlink.len().max(BACKLINK_SIZE), 0x00);
+        data.extend(&backlink[..header_size - data.len()]);
+
+        data
+    }
+
+    /// dBase II, its descriptors are 16 bytes long
+    fn level2_header(&self, record_length: u16) -> Vec<u8> {
+        let (year, month, day) = self.last_update;
+        let mut data = vec![self.version];
+        data.extend((self.records.len() as u16).to_le_bytes());
+        data.extend([month, day, year]);
+        data.extend(record_length.to_le_bytes());
+
+        for field in &self.fields {
+            let mut descriptor = field.name.as_bytes().to_vec();
+            descriptor.resize(11, 0x00);
+            // dBase II leaves garbage where fields start
+            descriptor.extend([field.field_type, field.length as u8, 0x34, 0x12]);
+            descriptor.push(field.decimal);
+            data.extend(descriptor);
+        }
+        data.push(0x0d);
+        data.resize(LEVEL2_HEADER_SIZE, 0x00);
+
+        data
+    }
+
+    /// dBase 7, with the language driver name and 48 bytes descriptors
+    fn level7_header(&self, record_length: u16) -> Vec<u8> {
+        let header_size = 68 + 48 * self.fields.len() + 1 + self.properties.len();
+
+        let (year, month, day) = self.last_update;
+        let mut data = vec![self.version, year, month, day];
+        data.extend((self.records.len() as u32).to_le_bytes());
+        data.extend((header_size as u16).to_le_bytes());
+        data.extend(record_length.to_le_bytes());
+        data.resize(28, 0x00);
+        data.extend([self.flags, self.language_driver]);
+        data.resize(32, 0x00);
+
+        let mut name = self.language_driver_name.as_bytes().to_vec();
+        name.resize(36, 0x00);
+        data.extend(name);
+
+        for field in &self.fields {
+            let mut descriptor = field.name.as_bytes().to_vec();
+            descriptor.resize(32, 0x00);
+            descriptor.extend([field.field_type, field.length as u8, field.decimal]);
+            descriptor.resize(48, 0x00);
+            data.extend(descriptor);
+        }
+        data.push(0x0d);
+        data.extend(&self.properties);
+
+        data
+    }
+}
@@ -1,41 +1,213 @@
 use crate::SliceUntilTerminator;
 use crate::errors::Error;
-use crate::errors::Error::FileFormat;
-use byteorder::ReadBytesExt;
-use std::io::{Read, Seek, SeekFrom};
+use crate::errors::Error::{FileFormat, NotSupported};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::io::{Read, Seek};
 use strum::{Display, FromRepr};
 use time::Date;
 
-#[derive(Debug, PartialEq, FromRepr, Display)]
+/// The version byte at the start of a DBF table
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromRepr, Display)]
 #[repr(u8)]
-pub(crate) enum DbfVersion {
+pub enum DbfVersion {
+    #[strum(to_string = "FoxBASE or dBase II file")]
+    FoxBase = 0x02,
     #[strum(to_string = "DBase file without memo")]
     Dbase = 0x03,
     #[strum(to_string = "DBase 3 file with memo")]
     Dbase3WithMemo = 0x83,
     #[strum(to_string = "DBase 4/5 file with memo")]
     Dbase4WithMemo = 0x8b,
+    #[strum(to_string = "DBase 4 SQL table without memo")]
+    Dbase4SqlTable = 0x43,
+    #[strum(to_string = "DBase 4 SQL system table without memo")]
+    Dbase4SqlSystem = 0x63,
+    #[strum(to_string = "DBase 4 SQL system table with memo")]
+    Dbase4SqlSystemWithMemo = 0xb3,
+    #[strum(to_string = "DBase 4 SQL table with memo")]
+    Dbase4SqlTableWithMemo = 0xcb,
+    #[strum(to_string = "FoxBASE+ file with memo")]
+    FoxBaseWithMemo = 0xfb,
     #[strum(to_string = "FoxPro file with memo")]
     FoxProWithMemo = 0xf5,
     #[strum(to_string = "Visual FoxPro without memo")]
     VisualFoxPro = 0x30,
+    #[strum(to_string = "Visual FoxPro with autoincrement")]
+    VisualFoxProAutoincrement = 0x31,
+    #[strum(to_string = "Visual FoxPro with varchar or varbinary")]
+    VisualFoxProVarchar = 0x32,
+    #[strum(to_string = "DBase 7 file without memo")]
+    Dbase7 = 0x04,
+    #[strum(to_string = "DBase 7 file with memo")]
+    Dbase7WithMemo = 0x8c,
+    #[strum(to_string = "Clipper SIX file with SMT memo")]
+    ClipperSix = 0xe5,
 }
 
-#[cfg_attr(not(test), expect(dead_code))]
-pub(crate) struct Header {
+/// How a memo file is laid out, it depends on who wrote the table
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MemoLayout {
+    Dbt3,
+    Dbt4,
+    Fpt,
+    Smt,
+}
+
+/// We don't know how Clipper SIX lays out its memo files
+pub(crate) fn smt_not_supported() -> Error {
+    NotSupported("Clipper SIX memo files (SMT)".to_string())
+}
+
+impl DbfVersion {
+    /// Tells us if this is a dBase II (or FoxBASE) table with
+    /// its own smaller header and field descriptors
+    pub fn is_level2(&self) -> bool {
+        matches!(self, DbfVersion::FoxBase)
+    }
+
+    /// Tells us if this is a level 7 table (dBase 7) with its
+    /// own header and field descriptor layout
+    pub fn is_level7(&self) -> bool {
+        matches!(self, DbfVersion::Dbase7 | DbfVersion::Dbase7WithMemo)
+    }
+
+    /// Tells us if this is a Visual FoxPro table, they have
+    /// field flags and a backlink to their database
+    pub fn is_visual_foxpro(&self) -> bool {
+        matches!(
+            self,
+            DbfVersion::VisualFoxPro
+                | DbfVersion::VisualFoxProAutoincrement
+                | DbfVersion::VisualFoxProVarchar
+        )
+    }
+
+    /// Tells us if this table was written by Clipper, it keeps
+    /// character fields longer than 255 bytes
+    pub fn is_clipper(&self) -> bool {
+        matches!(self, DbfVersion::ClipperSix)
+    }
+
+    /// Returns the layout for the memo file, `None` when
+    /// this version can't have memo fields
+    pub(crate) fn memo_layout(&self) -> Option<MemoLayout> {
+        match self {
+            DbfVersion::FoxBase
+            | DbfVersion::Dbase
+            | DbfVersion::Dbase4SqlTable
+            | DbfVersion::Dbase4SqlSystem
+            | DbfVersion::Dbase7 => None,
+            // FoxBASE+ took its memo files from dBase III
+            DbfVersion::Dbase3WithMemo | DbfVersion::FoxBaseWithMemo => Some(MemoLayout::Dbt3),
+            DbfVersion::Dbase4WithMemo
+            | DbfVersion::Dbase4SqlSystemWithMemo
+            | DbfVersion::Dbase4SqlTableWithMemo
+            | DbfVersion::Dbase7WithMemo => Some(MemoLayout::Dbt4),
+            // VFP doesn't mark tables with memo, they may always have one
+            DbfVersion::FoxProWithMemo
+            | DbfVersion::VisualFoxPro
+            | DbfVersion::VisualFoxProAutoincrement
+            | DbfVersion::VisualFoxProVarchar => Some(MemoLayout::Fpt),
+            DbfVersion::ClipperSix => Some(MemoLayout::Smt),
+        }
+    }
+}
+
+const PRODUCTION_INDEX: u8 = 0x01;
+
+/// Table information stored in the DBF header
+#[derive(Clone, Debug)]
+pub struct Header {
     pub(crate) version: DbfVersion,
     pub(crate) last_update: Date,
     pub(crate) num_records: u32,
     pub(crate) record_start: u16,
     pub(crate) record_length: u16,
+    pub(crate) incomplete_transaction: bool,
+    pub(crate) encrypted: bool,
+    pub(crate) reserved: [u8; 16],
+    pub(crate) flags: u8,
+    pub(crate) language_driver: u8,
+    pub(crate) language_driver_name: Option<String>,
+    pub(crate) database: Option<String>,
+}
+
+impl Header {
+    /// Returns the version byte for this table
+    pub fn version(&self) -> DbfVersion {
+        self.version
+    }
+
+    /// Returns the date of the last update,
+    /// dBase 3 and every FoxPro store years since 1900 so it can be wrong
+    pub fn last_update(&self) -> Date {
+        self.last_update
+    }
+
+    /// Returns the number of records, including deleted ones
+    pub fn num_records(&self) -> u32 {
+        self.num_records
+    }
+
+    /// Returns the position of the first record in the file
+    pub fn record_start(&self) -> u16 {
+        self.record_start
+    }
+
+    /// Returns the size of each record, including the deleted flag
+    pub fn record_length(&self) -> u16 {
+        self.record_length
+    }
+
+    /// Returns the language driver ID used to know the code page
+    pub fn language_driver(&self) -> u8 {
+        self.language_driver
+    }
+
+    /// Returns the language driver name, only dBase 7 stores it
+    pub fn language_driver_name(&self) -> Option<&str> {
+        self.language_driver_name.as_deref()
+    }
+
+    /// Tells us if there is a production index (MDX in dBase, CDX in FoxPro)
+    pub fn has_production_index(&self) -> bool {
+        self.flags & PRODUCTION_INDEX != 0
+    }
+
+    /// Tells us if dBase IV left a transaction unfinished
+    pub fn is_incomplete_transaction(&self) -> bool {
+        self.incomplete_transaction
+    }
+
+    /// Tells us if dBase IV encrypted the table
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    /// Returns the path to the database (DBC) this table belongs to,
+    /// only Visual FoxPro stores it and free tables don't have one
+    pub fn database(&self) -> Option<&str> {
+        self.database.as_deref()
+    }
+
+    /// Returns the raw table flags byte, its meaning depends on the version
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
 }
 
 /// The field (column) type and its constraints
 #[derive(Clone, Copy, Debug)]
 pub enum FieldType {
-    Character(u8),
-    Numeric { size: u8, decimal: u8 },
-    Float { size: u8, decimal: u8 },
+    Character(u16),
+    Numeric {
+        size: u8,
+        decimal: u8,
+    },
+    Float {
+        size: u8,
+        decimal: u8,
+    },
     Date,
     Logical,
     Memo,
@@ -43,36 +215,239 @@ pub enum FieldType {
     Integer,
     Currency,
     DateTime,
-    Double { decimal: u8 },
+    Double {
+        decimal: u8,
+    },
+    /// Visual FoxPro 9 variable length text, up to the given size
+    Varchar(u8),
+    /// Visual FoxPro 9 variable length bytes, up to the given size
+    Varbinary(u8),
+    /// Visual FoxPro binary data kept in the memo file
+    Blob,
+    /// OLE object kept in the memo file
+    General,
+    /// Visual FoxPro picture kept in the memo file
+    Picture,
+    // dBase 7
+    Timestamp,
+    Long,
+    Autoincrement,
+    Dbase7Double,
+    // Visual FoxPro system field with a bit for each nullable field
+    NullFlags(u8),
+}
+
+impl FieldType {
+    /// Tells us if the value is kept in the memo file
+    pub fn is_memo(&self) -> bool {
+        matches!(
+            self,
+            FieldType::Memo | FieldType::Blob | FieldType::General | FieldType::Picture
+        )
+    }
+}
+
+const SYSTEM: u8 = 0x01;
+const NULLABLE: u8 = 0x02;
+const BINARY: u8 = 0x04;
+const AUTOINCREMENT: u8 = 0x08;
+
+/// Visual FoxPro field flags and autoincrement state,
+/// other tables don't have them so everything is off
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FieldFlags {
+    pub(crate) bits: u8,
+    pub(crate) next_value: u32,
+    pub(crate) step: u8,
+    /// bit in `_NullFlags` telling us the field is null
+    pub(crate) null_bit: Option<u16>,
+    /// bit in `_NullFlags` telling us the last byte has the length
+    pub(crate) length_bit: Option<u16>,
+}
+
+impl FieldFlags {
+    /// Tells us if this is a hidden system field, like `_NullFlags`
+    pub fn is_system(&self) -> bool {
+        self.bits & SYSTEM != 0
+    }
+
+    /// Tells us if this field can store null values
+    pub fn is_nullable(&self) -> bool {
+        self.bits & NULLABLE != 0
+    }
+
+    /// Tells us if this field is binary (NOCPTRANS), so text is not translated
+    pub fn is_binary(&self) -> bool {
+        self.bits & BINARY != 0
+    }
+
+    /// Tells us if this field is autoincrementing
+    pub fn is_autoincrement(&self) -> bool {
+        self.bits & AUTOINCREMENT != 0
+    }
+
+    /// Returns the next value for an autoincrement field
+    pub fn next_value(&self) -> Option<u32> {
+        self.is_autoincrement().then_some(self.next_value)
+    }
+
+    /// Tells us if the field may store less than its size,
+    /// the real length is then kept in its last byte
+    pub fn is_variable_length(&self) -> bool {
+        self.length_bit.is_some()
+    }
+
+    /// Returns the step for an autoincrement field
+    pub fn step(&self) -> Option<u8> {
+        self.is_autoincrement().then_some(self.step)
+    }
+}
+
+/// Fields without the system ones, those are always at the end
+pub(crate) fn visible_fields(fields: &[Field]) -> &[Field] {
+    let visible = fields
+        .iter()
+        .position(|field| field.flags.is_system())
+        .unwrap_or(fields.len());
+
+    &fields[..visible]
 }
 
 /// A field (column) defined in a given DBF table
+#[derive(Clone, Debug)]
 pub struct Field {
     pub(crate) name: String,
     pub(crate) offset: u16,
+    pub(crate) length: u16,
+    pub(crate) decimal: u8,
     pub(crate) field_type: FieldType,
+    pub(crate) displacement: u32,
+    pub(crate) flags: FieldFlags,
 }
 
 impl Field {
-    pub(crate) fn new<R: Read + Seek>(reader: &mut R, offset: u16) -> Result<Self, Error> {
+    pub(crate) fn new<R: Read + Seek>(
+        reader: &mut R,
+        offset: u16,
+        version: DbfVersion,
+    ) -> Result<Self, Error> {
         let mut name = [0u8; 11];
         reader.read_exact(&mut name)?;
         let name = name.until_terminator(&[0]);
         let name = String::from_utf8_lossy(name);
 
         let field_type = reader.read_u8()?;
-        reader.seek(SeekFrom::Current(4))?;
 
-        let field_type = match field_type {
-            0x43 => {
-                // Read length
-                let length = reader.read_u8()?;
-                FieldType::Character(length)
+        // FoxPro writes where the field starts in the record,
+        // dBase uses these bytes in memory so they are garbage
+        let displacement = reader.read_u32::<LittleEndian>()?;
+
+        // every descriptor carries length and decimal places,
+        // even when the type has a well known size
+        let length = reader.read_u8()?;
+        let decimal = reader.read_u8()?;
+
+        let field_type = Self::parse_type(field_type, length, decimal, false)?;
+
+        // Clipper uses the decimal count as the high byte for long characters
+        let (length, decimal, field_type) = match field_type {
+            FieldType::Character(_) if version.is_clipper() => {
+                let length = u16::from_le_bytes([length, decimal]);
+                (length, 0, FieldType::Character(length))
+            }
+            field_type => (length as u16, decimal, field_type),
+        };
+
+        // dBase keeps multi-user data here, VFP its flags and autoincrement
+        let flags = if version.is_visual_foxpro() {
+            FieldFlags {
+                bits: reader.read_u8()?,
+                next_value: reader.read_u32::<LittleEndian>()?,
+                step: reader.read_u8()?,
+                null_bit: None,
+                length_bit: None,
             }
+        } else {
+            FieldFlags::default()
+        };
+
+        Ok(Self {
+            name: name.into_owned(),
+            offset,
+            length,
+            decimal,
+            field_type,
+            displacement,
+            flags,
+        })
+    }
+
+    /// Reads a dBase II (or FoxBASE) field descriptor, they are 16 bytes long
+    pub(crate) fn new_level2<R: Read + Seek>(reader: &mut R, offset: u16) -> Result<Self, Error> {
+        let mut name = [0u8; 11];
+        reader.read_exact(&mut name)?;
+        let name = name.until_terminator(&[0]);
+        let name = String::from_utf8_lossy(name);
+
+        let field_type = reader.read_u8()?;
+        let length = reader.read_u8()?;
+
+        // where the field was in memory, garbage for us
+        reader.read_u16::<LittleEndian>()?;
+        let decimal = reader.read_u8()?;
+
+        let field_type = Self::parse_type(field_type, length, decimal, false)?;
+        let length = length as u16;
+
+        Ok(Self {
+            name: name.into_owned(),
+            offset,
+            length,
+            decimal,
+            field_type,
+            displacement: 0,
+            flags: FieldFlags::default(),
+        })
+    }
+
+    /// Reads a dBase 7 field descriptor, they are 48 bytes long
+    /// with names up to 32 characters
+    pub(crate) fn new_level7<R: Read + Seek>(reader: &mut R, offset: u16) -> Result<Self, Error> {
+        let mut name = [0u8; 32];
+        reader.read_exact(&mut name)?;
+        let name = name.until_terminator(&[0]);
+        let name = String::from_utf8_lossy(name);
+
+        let field_type = reader.read_u8()?;
+        let length = reader.read_u8()?;
+        let decimal = reader.read_u8()?;
+
+        let field_type = Self::parse_type(field_type, length, decimal, true)?;
+        let length = length as u16;
+
+        // the rest is the MDX flag and autoincrement next value
+
+        Ok(Self {
+            name: name.into_owned(),
+            offset,
+            length,
+            decimal,
+            field_type,
+            displacement: 0,
+            flags: FieldFlags::default(),
+        })
+    }
+
+    fn parse_type(
+        field_type: u8,
+        length: u8,
+        decimal: u8,
+        level7: bool,
+    ) -> Result<FieldType, Error> {
+        let field_type = match field_type {
+            0x43 => FieldType::Character(length as u16),
             what @ (0x4e | 0x42 | 0x46) => {
-                // Read length and decimal places
-                let size = reader.read_u8()?;
-                let decimal = reader.read_u8()?;
+                let size = length;
                 if what == 0x4e {
                     FieldType::Numeric { size, decimal }
                 } else if what == 0x42 {
@@ -84,25 +459,46 @@ impl Field {
             0x44 => FieldType::Date,
             0x4c => FieldType::Logical,
             0x4d => FieldType::Memo,
+            // dBase 7 and VFP store integers in a different way
+            0x49 if level7 => FieldType::Long,
             0x49 => FieldType::Integer,
             0x59 => FieldType::Currency,
             0x54 => FieldType::DateTime,
+            0x40 => FieldType::Timestamp,
+            0x2b => FieldType::Autoincrement,
+            0x4f => FieldType::Dbase7Double,
+            0x30 => FieldType::NullFlags(length),
+            0x56 => FieldType::Varchar(length),
+            0x51 => FieldType::Varbinary(length),
+            0x57 => FieldType::Blob,
+            0x47 => FieldType::General,
+            0x50 => FieldType::Picture,
             _ => return Err(FileFormat(format!("invalid field type: {field_type}"))),
         };
 
-        // While Field info is 32 bytes, we don't have much
-        // to read for now, it has more info like autoincrement,
-        // indices, etc...
+        // fixed size values are read whole, any other length would
+        // have us read past the field or only part of it
+        let expected = match field_type {
+            FieldType::Logical => 1,
+            FieldType::Integer | FieldType::Long | FieldType::Autoincrement => 4,
+            FieldType::Date
+            | FieldType::Currency
+            | FieldType::DateTime
+            | FieldType::Dbase7Double
+            | FieldType::Timestamp => 8,
+            _ => return Ok(field_type),
+        };
+        if length != expected {
+            return Err(FileFormat(format!(
+                "{field_type:?} fields are {expected} bytes long, not {length}"
+            )));
+        }
 
-        Ok(Self {
-            name: name.into_owned(),
-            offset,
-            field_type,
-        })
+        Ok(field_type)
     }
 
     /// Returns the name for this field (column)
-    /// names are limited to 11 ASCII characters
+    /// names are limited to 11 ASCII characters (32 in dBase 7)
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -112,18 +508,136 @@ impl Field {
         self.field_type
     }
 
+    /// Returns the Visual FoxPro flags for this field (column)
+    pub fn flags(&self) -> FieldFlags {
+        self.flags
+    }
+
+    /// Returns the length of this field in the record,
+    /// as the descriptor says so even for types with a well known size
     pub fn size(&self) -> u16 {
-        match self.field_type {
-            FieldType::Character(size) => size as u16,
-            FieldType::Numeric { size, .. } => size as u16,
-            FieldType::Float { size, .. } => size as u16,
-            FieldType::Date => 8,
-            FieldType::Logical => 1,
-            FieldType::Memo => 10,
-            FieldType::Integer => 4,
-            FieldType::Double { .. } => 8,
-            FieldType::Currency => 8,
-            FieldType::DateTime => 8,
+        self.length
+    }
+
+    /// Returns the decimal count from the descriptor
+    pub fn decimal(&self) -> u8 {
+        self.decimal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dbf::header::{DbfVersion, Field, FieldType, MemoLayout};
+    use crate::errors::Error::FileFormat;
+    use std::io::Cursor;
+
+    fn descriptor(flags: u8, next_value: u32, step: u8) -> Vec<u8> {
+        let mut data = b"ID\0\0\0\0\0\0\0\0\0I".to_vec();
+        data.extend([0x01, 0x00, 0x00, 0x00, 0x04, 0x00, flags]);
+        data.extend(next_value.to_le_bytes());
+        data.push(step);
+        data.resize(32, 0x00);
+        data
+    }
+
+    #[test]
+    fn vfp_field_flags() -> anyhow::Result<()> {
+        let mut reader = Cursor::new(descriptor(0x0c, 10, 2));
+        let field = Field::new(&mut reader, 1, DbfVersion::VisualFoxPro)?;
+
+        assert!(matches!(field.field_type(), FieldType::Integer));
+        let flags = field.flags();
+        assert!(flags.is_autoincrement());
+        assert!(flags.is_binary());
+        assert!(!flags.is_nullable());
+        assert!(!flags.is_system());
+        assert_eq!(Some(10), flags.next_value());
+        assert_eq!(Some(2), flags.step());
+
+        let mut reader = Cursor::new(descriptor(0x02, 0, 0));
+        let field = Field::new(&mut reader, 1, DbfVersion::VisualFoxPro)?;
+        assert!(field.flags().is_nullable());
+        assert_eq!(None, field.flags().next_value());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dbase_has_no_field_flags() -> anyhow::Result<()> {
+        let mut reader = Cursor::new(descriptor(0x0c, 10, 2));
+        let field = Field::new(&mut reader, 1, DbfVersion::Dbase)?;
+
+        assert!(!field.flags().is_autoincrement());
+        assert_eq!(None, field.flags().step());
+
+        Ok(())
+    }
+
+    #[test]
+    fn clipper_long_characters() -> anyhow::Result<()> {
+        let mut data = b"NOTES\0\0\0\0\0\0C".to_vec();
+        data.extend([0x00, 0x00, 0x00, 0x00, 0x2c, 0x01]);
+        data.resize(32, 0x00);
+
+        let field = Field::new(&mut Cursor::new(&data), 1, DbfVersion::ClipperSix)?;
+        assert!(matches!(field.field_type(), FieldType::Character(300)));
+        assert_eq!(300, field.size());
+        assert_eq!(0, field.decimal());
+
+        // everybody else only has a decimal count that makes no sense
+        let field = Field::new(&mut Cursor::new(&data), 1, DbfVersion::Dbase)?;
+        assert_eq!(44, field.size());
+
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_size_fields_need_their_size() -> anyhow::Result<()> {
+        for (field_type, length) in [(b'L', 0), (b'D', 6), (b'I', 2), (b'T', 4)] {
+            let mut data = b"BROKEN\0\0\0\0\0".to_vec();
+            data.push(field_type);
+            data.extend([0x01, 0x00, 0x00, 0x00, length, 0x00]);
+            data.resize(32, 0x00);
+
+            let field = Field::new(&mut Cursor::new(&data), 1, DbfVersion::VisualFoxPro);
+            assert!(
+                matches!(&field, Err(FileFormat(message)) if message.contains("bytes long")),
+                "{field:?}"
+            );
+        }
+
+        let mut data = b"ADDED\0\0\0\0\0\0D".to_vec();
+        data.extend([0x00, 0x00, 0x00, 0x00, 8, 0]);
+        data.resize(32, 0x00);
+        let field = Field::new(&mut Cursor::new(&data), 1, DbfVersion::Dbase)?;
+        assert!(matches!(field.field_type(), FieldType::Date));
+
+        Ok(())
+    }
+
+    #[test]
+    fn memo_layout_for_versions() {
+        let layouts = [
+            (0x02, None),
+            (0x03, None),
+            (0x43, None),
+            (0x63, None),
+            (0x83, Some(MemoLayout::Dbt3)),
+            (0xfb, Some(MemoLayout::Dbt3)),
+            (0x8b, Some(MemoLayout::Dbt4)),
+            (0xb3, Some(MemoLayout::Dbt4)),
+            (0xcb, Some(MemoLayout::Dbt4)),
+            (0x8c, Some(MemoLayout::Dbt4)),
+            (0xf5, Some(MemoLayout::Fpt)),
+            (0x30, Some(MemoLayout::Fpt)),
+            (0x31, Some(MemoLayout::Fpt)),
+            (0x32, Some(MemoLayout::Fpt)),
+            (0xe5, Some(MemoLayout::Smt)),
+        ];
+
+        for (version, layout) in layouts {
+            let version = DbfVersion::from_repr(version).expect("known version");
+            assert_eq!(layout, version.memo_layout(), "{version}");
         }
     }
 }
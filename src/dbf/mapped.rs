@@ -0,0 +1,347 @@
+use crate::dbf::codepage::CodePage;
+use crate::dbf::header::{Field, Header, visible_fields};
+use crate::dbf::partition::split_records;
+use crate::dbf::properties::FieldProperty;
+use crate::dbf::reader::{find_memo, read_memo, read_table_info};
+use crate::dbf::rows::{ColumnIndex, RowFilter, RowRef, find_column};
+use crate::errors::Error;
+use crate::errors::Error::{FileFormat, RecordOutOfBounds};
+use crate::memo::{MemoRead, SharedMemo};
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// A DBF table reader over bytes in memory, usually a memory-mapped file.
+/// Rows borrow their data from it so nothing is read or copied per record,
+/// and unlike `DbfReader` many iterators can be used at the same time
+pub struct MappedDbfReader<D: AsRef<[u8]> = Mmap> {
+    data: D,
+    memo: Option<SharedMemo>,
+    header: Header,
+    code_page: CodePage,
+    fields: Arc<Vec<Field>>,
+    properties: Vec<FieldProperty>,
+    filter: RowFilter,
+}
+
+impl<D: AsRef<[u8]>> MappedDbfReader<D> {
+    /// Creates a DBF parser from the bytes of a whole table
+    pub fn from_bytes(data: D) -> Result<Self, Error> {
+        let table = read_table_info(&mut Cursor::new(data.as_ref()))?;
+
+        Ok(Self {
+            data,
+            header: table.header,
+            code_page: table.code_page,
+            memo: None,
+            fields: Arc::new(table.fields),
+            properties: table.properties,
+            filter: RowFilter::All,
+        })
+    }
+
+    /// Sets a memo reader for memo fields
+    pub fn with_memo(mut self, memo: impl MemoRead + Send + 'static) -> Self {
+        self.memo = Some(Arc::new(Mutex::new(memo)));
+        self
+    }
+
+    /// Table information from the header
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Sets the code page used for text, overriding the one from the header,
+    /// tables without a language driver we know are read as CP437
+    pub fn with_code_page(mut self, code_page: CodePage) -> Self {
+        self.code_page = code_page;
+        self
+    }
+
+    /// Code page used for text in this table and its memos
+    pub fn code_page(&self) -> CodePage {
+        self.code_page
+    }
+
+    /// Fields defined in this DBF table
+    /// system fields (like `_NullFlags` in VFP) are not included
+    pub fn fields(&self) -> &[Field] {
+        visible_fields(&self.fields)
+    }
+
+    /// Every field defined in this DBF table, including system fields
+    pub fn all_fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Finds a column by its name once, rows read it with `get_by_handle`
+    pub fn column(&self, name: &str) -> Result<ColumnIndex, Error> {
+        find_column(&self.fields, name)
+    }
+
+    /// Field properties (required, min, max, default and custom ones),
+    /// only dBase 7 tables have them
+    pub fn properties(&self) -> &[FieldProperty] {
+        &self.properties
+    }
+
+    /// Sets which rows we get depending on their deleted flag,
+    /// by default every row is returned
+    pub fn with_filter(mut self, filter: RowFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Which rows we get depending on their deleted flag
+    pub fn filter(&self) -> RowFilter {
+        self.filter
+    }
+
+    /// Returns iterator to rows in the DBF table borrowing from the map,
+    /// deleted rows are returned depending on `filter`
+    #[must_use]
+    pub fn rows(&self) -> MappedRows<'_> {
+        MappedRows {
+            data: self.data.as_ref(),
+            record_size: self.header.record_length as usize,
+            record_start: self.header.record_start as usize,
+            fields: &self.fields,
+            memo: &self.memo,
+            code_page: self.code_page,
+            filter: self.filter,
+            current: 0,
+            total: self.header.num_records,
+        }
+    }
+
+    /// Returns iterator to rows starting at the given record,
+    /// records are numbered from 0
+    pub fn rows_from(&self, record: u32) -> Result<MappedRows<'_>, Error> {
+        let total = self.header.num_records;
+        if record > total {
+            return Err(RecordOutOfBounds(record, total));
+        }
+
+        let mut rows = self.rows();
+        rows.current = record;
+        Ok(rows)
+    }
+
+    /// Splits the table in `count` partitions of consecutive records,
+    /// they borrow from the same map so they can go to different threads
+    pub fn partitions(&self, count: usize) -> Vec<MappedRows<'_>> {
+        split_records(self.header.num_records, count)
+            .into_iter()
+            .map(|records| {
+                let mut rows = self.rows();
+                rows.current = records.start;
+                rows.total = records.end;
+                rows
+            })
+            .collect()
+    }
+
+    /// Reads a single record even if it is deleted, records are numbered from 0
+    pub fn record(&self, record: u32) -> Result<RowRef<'_>, Error> {
+        let total = self.header.num_records;
+        self.rows_from(record)?
+            .with_filter(RowFilter::All)
+            .next()
+            .ok_or(RecordOutOfBounds(record, total))?
+    }
+}
+
+impl MappedDbfReader<Mmap> {
+    /// Maps a DBF table from a path, when the table has memo fields
+    /// its memo file (DBT or FPT) is found and mapped as well.
+    ///
+    /// The files must not be truncated by someone else while they are mapped
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut dbf = Self::from_bytes(map_file(path)?)?;
+
+        if let Some((memo_path, layout)) = find_memo(path, dbf.header.version, &dbf.fields)? {
+            dbf.memo = Some(read_memo(layout, Cursor::new(map_file(&memo_path)?))?);
+        }
+
+        Ok(dbf)
+    }
+}
+
+fn map_file(path: &Path) -> Result<Mmap, Error> {
+    let file = File::open(path)?;
+    // SAFETY: we only read from the map, it is only unsound when the file
+    // is truncated while mapped and `open` asks its callers not to do that
+    let map = unsafe { Mmap::map(&file)? };
+    Ok(map)
+}
+
+/// Rows borrowing their data from a `MappedDbfReader`
+pub struct MappedRows<'a> {
+    data: &'a [u8],
+    record_size: usize,
+    record_start: usize,
+    fields: &'a Arc<Vec<Field>>,
+    memo: &'a Option<SharedMemo>,
+    code_page: CodePage,
+    filter: RowFilter,
+    current: u32,
+    total: u32,
+}
+
+impl<'a> MappedRows<'a> {
+    /// Sets which rows we get depending on their deleted flag
+    pub fn with_filter(mut self, filter: RowFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+impl<'a> Iterator for MappedRows<'a> {
+    type Item = Result<RowRef<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.current >= self.total {
+                return None;
+            }
+            let record = self.current;
+            self.current += 1;
+
+            let start = self.record_start + self.record_size * record as usize;
+            let Some(data) = self.data.get(start..start + self.record_size) else {
+                // a truncated table has no more records after this one
+                self.current = self.total;
+                return Some(Err(FileFormat(format!(
+                    "record {record} is past the end of the table"
+                ))));
+            };
+
+            if !self.filter.accepts(data[0]) {
+                continue;
+            }
+
+            let row = RowRef::new(self.fields, self.memo, self.code_page, record, data);
+            return Some(Ok(row));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.total - self.current) as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dbf::mapped::MappedDbfReader;
+    use crate::dbf::reader::DbfReader;
+    use crate::dbf::rows::{RowFilter, Value};
+    use crate::errors::Error::{FileFormat, RecordOutOfBounds};
+    use crate::{sample_file, sample_path};
+    use std::io::Read;
+    use std::thread;
+
+    #[test]
+    fn mapped_rows_match_rows() -> anyhow::Result<()> {
+        for name in ["db3.dbf", "vfp.dbf", "db4.dbf", "vfpmemo.dbf"] {
+            let mut dbf = DbfReader::open(sample_path(name))?;
+            let mapped = MappedDbfReader::open(sample_path(name))?;
+            assert_eq!(dbf.all_fields().len(), mapped.all_fields().len());
+
+            let mut count = 0;
+            for (expected, row) in dbf.rows().zip(mapped.rows()) {
+                let (expected, row) = (expected?, row?);
+                assert_eq!(expected.record(), row.record());
+                // errors are compared too, some samples have invalid values
+                for index in 0..expected.fields().len() {
+                    let value = row.get_by_index(index).map_err(|err| err.to_string());
+                    let expected = expected.get_by_index(index).map_err(|err| err.to_string());
+                    assert_eq!(expected, value, "{name} record {count} field {index}");
+                }
+                count += 1;
+            }
+            assert_eq!(dbf.header().num_records(), count);
+        }
+
+        let mapped = MappedDbfReader::open(sample_path("vfpmemo.dbf"))?;
+        assert_eq!(
+            Value::Memo("Just a brief note.".to_string()),
+            mapped.record(0)?.get("MEMO")?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn mapped_records() -> anyhow::Result<()> {
+        let mapped = MappedDbfReader::open(sample_path("db3.dbf"))?.with_filter(RowFilter::Live);
+
+        // records are read even when deleted
+        let row = mapped.record(2)?;
+        assert!(row.is_deleted());
+        assert_eq!("Thingamajig", row.get_str("NAME")?);
+
+        // and rows can be read at the same time
+        let names = mapped.rows().zip(mapped.rows_from(3)?);
+        assert_eq!(4, names.count());
+
+        assert!(matches!(mapped.record(8), Err(RecordOutOfBounds(8, 8))));
+        assert!(matches!(mapped.rows_from(9), Err(RecordOutOfBounds(9, 8))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn mapped_partitions() -> anyhow::Result<()> {
+        let mapped = MappedDbfReader::open(sample_path("vfp.dbf"))?;
+
+        let names = thread::scope(|scope| {
+            let handles = mapped
+                .partitions(3)
+                .into_iter()
+                .map(|partition| {
+                    scope.spawn(move || {
+                        partition
+                            .map(|row| Ok(row?.get_str("NAME")?))
+                            .collect::<anyhow::Result<Vec<_>>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<anyhow::Result<Vec<_>>>()
+        })?;
+
+        assert_eq!(
+            vec![3, 2, 2],
+            names.iter().map(Vec::len).collect::<Vec<_>>()
+        );
+        assert_eq!("Widget Pro", names[0][0]);
+        assert_eq!("Broken Item", names[2][1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mapped_truncated_table() -> anyhow::Result<()> {
+        let mut data = vec![];
+        sample_file("db3.dbf")?.read_to_end(&mut data)?;
+        data.truncate(data.len() - 60);
+
+        let mapped = MappedDbfReader::from_bytes(data)?;
+        let rows = mapped.rows().collect::<Vec<_>>();
+
+        assert_eq!(7, rows.len());
+        assert!(matches!(rows.last(), Some(Err(FileFormat(_)))));
+        assert_eq!(
+            Value::Character("Widget Pro".to_string()),
+            mapped.record(0)?.get("NAME")?
+        );
+
+        Ok(())
+    }
+}
@@ -1,4 +1,22 @@
+mod convert;
+mod de;
+#[cfg(test)]
+mod fixtures;
 mod header;
-mod row;
+mod partition;
+mod properties;
+mod rows;
 
+#[cfg(feature = "async")]
+pub mod async_reader;
+pub mod codepage;
+pub mod database;
+pub mod dialect;
+pub mod mapped;
 pub mod reader;
+pub mod stream;
+
+pub use header::{DbfVersion, Field, FieldFlags, FieldType, Header};
+pub use partition::PartitionRows;
+pub use properties::{FieldProperty, PropertyKind};
+pub use rows::{BufferedRows, ColumnIndex, Row, RowFilter, RowRef, Rows, Value};
@@ -0,0 +1,238 @@
+use crate::dbf::codepage::CodePage;
+use crate::dbf::header::{Field, Header};
+use crate::dbf::rows::{BLOCK_SIZE, Row, RowFilter};
+use crate::errors::Error;
+use crate::memo::SharedMemo;
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Splits records in `count` ranges of about the same size, fewer
+/// when there are not enough records for all of them
+pub(crate) fn split_records(total: u32, count: usize) -> Vec<Range<u32>> {
+    let count = (count.max(1) as u32).min(total.max(1));
+    let size = total / count;
+    let extra = total % count;
+
+    let mut start = 0;
+    (0..count)
+        .map(|index| {
+            // the first ranges take the records left over
+            let end = start + size + u32::from(index < extra);
+            let range = start..end;
+            start = end;
+            range
+        })
+        .collect()
+}
+
+/// Rows in a range of records read with positional reads, so every
+/// partition of a table can be read at the same time from its own thread
+pub struct PartitionRows<'a> {
+    file: &'a File,
+    record_size: u16,
+    record_start: u16,
+    fields: Arc<Vec<Field>>,
+    memo: Option<SharedMemo>,
+    code_page: CodePage,
+    filter: RowFilter,
+    buffer: Vec<u8>,
+    position: usize,
+    records: Range<u32>,
+    current: u32,
+}
+
+impl<'a> PartitionRows<'a> {
+    pub(crate) fn new(
+        file: &'a File,
+        header: &Header,
+        records: Range<u32>,
+        fields: Arc<Vec<Field>>,
+        memo: Option<SharedMemo>,
+        code_page: CodePage,
+        filter: RowFilter,
+    ) -> Self {
+        Self {
+            file,
+            record_size: header.record_length,
+            record_start: header.record_start,
+            fields,
+            memo,
+            code_page,
+            filter,
+            buffer: Vec::new(),
+            position: 0,
+            current: records.start,
+            records,
+        }
+    }
+
+    /// Records in this partition, numbered from 0
+    pub fn records(&self) -> Range<u32> {
+        self.records.clone()
+    }
+
+    fn read_block(&mut self) -> Result<(), Error> {
+        let record_size = self.record_size as usize;
+        let records = (BLOCK_SIZE / record_size)
+            .max(1)
+            .min((self.records.end - self.current) as usize);
+
+        let position =
+            (self.record_start as u64) + (self.record_size as u64) * (self.current as u64);
+
+        self.buffer.resize(records * record_size, 0);
+        read_exact_at(self.file, &mut self.buffer, position)?;
+        self.position = 0;
+
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for PartitionRows<'a> {
+    type Item = Result<Row, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.current >= self.records.end {
+                return None;
+            }
+
+            if self.position >= self.buffer.len()
+                && let Err(err) = self.read_block()
+            {
+                self.current = self.records.end;
+                return Some(Err(err));
+            }
+
+            let start = self.position;
+            let record = self.current;
+            self.position += self.record_size as usize;
+            self.current += 1;
+
+            if self.filter.accepts(self.buffer[start]) {
+                let data = self.buffer[start..self.position].to_vec();
+                let fields = Arc::clone(&self.fields);
+                return Some(Ok(Row::new(
+                    fields,
+                    self.memo.clone(),
+                    self.code_page,
+                    record,
+                    data,
+                )));
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn read_exact_at(file: &File, buffer: &mut [u8], offset: u64) -> io::Result<()> {
+    use std::os::unix::fs::FileExt;
+    file.read_exact_at(buffer, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buffer: &mut [u8], mut offset: u64) -> io::Result<()> {
+    use std::io::ErrorKind;
+    use std::os::windows::fs::FileExt;
+
+    // seek_read moves the cursor, but every partition reads at its own offsets
+    while !buffer.is_empty() {
+        match file.seek_read(buffer, offset) {
+            Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+            Ok(read) => {
+                buffer = &mut buffer[read..];
+                offset += read as u64;
+            }
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn read_exact_at(file: &File, buffer: &mut [u8], offset: u64) -> io::Result<()> {
+    use std::io::{Read, Seek, SeekFrom};
+    use std::sync::Mutex;
+
+    // without positional reads every partition moves the same cursor,
+    // so they take turns to seek and read
+    static CURSOR: Mutex<()> = Mutex::new(());
+    let _turn = CURSOR.lock().unwrap_or_else(|poison| poison.into_inner());
+
+    let mut file = file;
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dbf::partition::split_records;
+    use crate::dbf::reader::DbfReader;
+    use crate::dbf::rows::RowFilter;
+    use crate::sample_path;
+    use std::thread;
+
+    #[test]
+    fn split_records_evenly() {
+        assert_eq!(vec![0..4, 4..7, 7..10], split_records(10, 3));
+        assert_eq!(vec![0..1, 1..2], split_records(2, 8));
+        assert_eq!(vec![0..5], split_records(5, 0));
+        assert_eq!(vec![0..0], split_records(0, 4));
+    }
+
+    #[test]
+    fn read_partitions() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::open(sample_path("vfpmemo.dbf"))?;
+        let expected = dbf
+            .rows()
+            .map(|row| Ok(row?.get("MEMO")?))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        for count in [1, 2, 3, 100] {
+            let partitions = dbf.partitions(count);
+            let values = thread::scope(|scope| {
+                let handles = partitions
+                    .into_iter()
+                    .map(|partition| {
+                        scope.spawn(move || {
+                            partition
+                                .map(|row| Ok(row?.get("MEMO")?))
+                                .collect::<anyhow::Result<Vec<_>>>()
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().unwrap())
+                    .collect::<anyhow::Result<Vec<_>>>()
+            })?;
+
+            assert_eq!(expected, values.into_iter().flatten().collect::<Vec<_>>());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn filter_partitions() -> anyhow::Result<()> {
+        let dbf = DbfReader::open(sample_path("db3.dbf"))?.with_filter(RowFilter::Deleted);
+        let partitions = dbf.partitions(4);
+        let records = partitions.iter().map(|partition| partition.records());
+        assert_eq!(vec![0..2, 2..4, 4..6, 6..8], records.collect::<Vec<_>>());
+
+        let mut records = vec![];
+        for partition in partitions {
+            for row in partition {
+                records.push(row?.record());
+            }
+        }
+        assert_eq!(vec![2, 5], records);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use crate::SliceUntilTerminator;
+use crate::dbf::header::Field;
+use crate::errors::Error;
+use crate::errors::Error::FileFormat;
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+const STANDARD_SIZE: u64 = 15;
+const CUSTOM_SIZE: u64 = 14;
+
+/// What a dBase 7 property describes
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyKind {
+    Required,
+    Min,
+    Max,
+    Default,
+    /// A database constraint, it belongs to the table and not a field
+    Constraint,
+    /// A property defined by the user with its name
+    Custom(String),
+}
+
+/// A property attached by dBase 7 to a field or the table,
+/// the value is stored as it is in the file
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldProperty {
+    pub(crate) field: Option<String>,
+    pub(crate) kind: PropertyKind,
+    pub(crate) value: Vec<u8>,
+}
+
+impl FieldProperty {
+    /// Returns the field name for this property, `None` for table properties
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    /// Returns what this property describes
+    pub fn kind(&self) -> &PropertyKind {
+        &self.kind
+    }
+
+    /// Returns the raw value, stored with the same format as the field
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+/// Reads the field properties structure dBase 7 writes right after
+/// the field descriptors, `size` is the room left before the records
+pub(crate) fn read_properties<R: Read + Seek>(
+    reader: &mut R,
+    size: u64,
+    fields: &[Field],
+) -> Result<Vec<FieldProperty>, Error> {
+    let mut data = Vec::with_capacity(size as usize);
+    reader.by_ref().take(size).read_to_end(&mut data)?;
+
+    let slice = |start: u16, length: u16| {
+        let start = start as usize;
+        let end = start + length as usize;
+        data.get(start..end)
+            .ok_or(FileFormat(format!("invalid field property at {start}")))
+    };
+    let field_name = |index: u16| match index {
+        // field offsets are one based, zero is the table
+        0 => Ok(None),
+        index => fields
+            .get(index as usize - 1)
+            .map(|field| Some(field.name().to_string()))
+            .ok_or(FileFormat(format!("invalid field in property: {index}"))),
+    };
+
+    let mut cursor = Cursor::new(&data[..]);
+    let standard_count = cursor.read_u16::<LittleEndian>()?;
+    let standard_start = cursor.read_u16::<LittleEndian>()?;
+    let custom_count = cursor.read_u16::<LittleEndian>()?;
+    let custom_start = cursor.read_u16::<LittleEndian>()?;
+
+    let mut properties = Vec::new();
+
+    for index in 0..standard_count as u64 {
+        cursor.seek(SeekFrom::Start(
+            standard_start as u64 + STANDARD_SIZE * index,
+        ))?;
+        let _generation = cursor.read_u16::<LittleEndian>()?;
+        let field = cursor.read_u16::<LittleEndian>()?;
+        let kind = match cursor.read_u8()? {
+            0x01 => PropertyKind::Required,
+            0x02 => PropertyKind::Min,
+            0x03 => PropertyKind::Max,
+            0x04 => PropertyKind::Default,
+            0x06 => PropertyKind::Constraint,
+            what => return Err(FileFormat(format!("invalid field property: {what}"))),
+        };
+
+        // field type, array flag and reserved bytes
+        cursor.seek(SeekFrom::Current(6))?;
+        let value_start = cursor.read_u16::<LittleEndian>()?;
+        let value_length = cursor.read_u16::<LittleEndian>()?;
+
+        properties.push(FieldProperty {
+            field: field_name(field)?,
+            kind,
+            value: slice(value_start, value_length)?.to_vec(),
+        });
+    }
+
+    for index in 0..custom_count as u64 {
+        cursor.seek(SeekFrom::Start(custom_start as u64 + CUSTOM_SIZE * index))?;
+        let _generation = cursor.read_u16::<LittleEndian>()?;
+        let field = cursor.read_u16::<LittleEndian>()?;
+
+        // field type and reserved byte
+        cursor.seek(SeekFrom::Current(2))?;
+        let name_start = cursor.read_u16::<LittleEndian>()?;
+        let name_length = cursor.read_u16::<LittleEndian>()?;
+        let value_start = cursor.read_u16::<LittleEndian>()?;
+        let value_length = cursor.read_u16::<LittleEndian>()?;
+
+        let name = slice(name_start, name_length)?.until_terminator(&[0]);
+        let name = String::from_utf8_lossy(name);
+
+        properties.push(FieldProperty {
+            field: field_name(field)?,
+            kind: PropertyKind::Custom(name.into_owned()),
+            value: slice(value_start, value_length)?.to_vec(),
+        });
+    }
+
+    Ok(properties)
+}
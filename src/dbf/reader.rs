@@ -1,102 +1,438 @@
-use crate::dbf::header::{DbfVersion, Field, Header};
-use crate::dbf::row::Rows;
+use crate::SliceUntilTerminator;
+use crate::dbf::codepage::CodePage;
+use crate::dbf::database::DatabaseTable;
+use crate::dbf::dialect::{DialectGuess, MemoEvidence, guess};
+use crate::dbf::header::{
+    DbfVersion, Field, FieldType, Header, MemoLayout, smt_not_supported, visible_fields,
+};
+use crate::dbf::partition::{PartitionRows, split_records};
+use crate::dbf::properties::{FieldProperty, read_properties};
+use crate::dbf::rows::{BufferedRows, ColumnIndex, Row, RowFilter, Rows, find_column};
 use crate::errors::Error;
-use crate::errors::Error::FileFormat;
-use crate::memo::MemoRead;
+use crate::errors::Error::{FileFormat, RecordOutOfBounds};
+use crate::memo::dbt::{Dbt3Reader, Dbt4Reader};
+use crate::memo::fpt::FptReader;
+use crate::memo::{MemoRead, SharedMemo};
 use byteorder::{LittleEndian, ReadBytesExt};
-use std::io::{Read, Seek, SeekFrom};
-use std::sync::Arc;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use time::{Date, Month};
 
 /// A DBF table reader
 /// used to get fields and rows from a DBF
 pub struct DbfReader<R: Read + Seek> {
     reader: R,
-    memo: Option<Box<dyn MemoRead>>,
+    memo: Option<SharedMemo>,
     header: Header,
+    code_page: CodePage,
     fields: Arc<Vec<Field>>,
+    properties: Vec<FieldProperty>,
+    filter: RowFilter,
+    memo_evidence: Option<MemoEvidence>,
 }
 
 const FIELD_START: u64 = 32;
 const FIELD_SIZE: u64 = 32;
 
-impl<R: Read + Seek> DbfReader<R> {
-    /// Creates a DBF parser from a reader
-    pub fn from_reader(mut reader: R) -> Result<Self, Error> {
-        reader.seek(SeekFrom::Start(0))?;
-        let version = reader.read_u8()?;
-        let version = DbfVersion::from_repr(version)
-            .ok_or(FileFormat(format!("invalid file version: {version}")))?;
+// dBase 7 has a language driver name before its bigger field descriptors
+const LEVEL7_FIELD_START: u64 = 68;
+const LEVEL7_FIELD_SIZE: u64 = 48;
 
-        let year = reader.read_u8()?;
-        let year = 1900 + (year as i32);
+// dBase II has room for 32 smaller field descriptors and their terminator
+const LEVEL2_FIELD_START: u64 = 8;
+const LEVEL2_FIELD_SIZE: u64 = 16;
+const LEVEL2_MAX_FIELDS: u64 = 32;
+const LEVEL2_HEADER_SIZE: usize = 521;
 
-        let month = reader.read_u8()?;
-        let month = Month::try_from(month)
-            .map_err(|_| FileFormat(format!("invalid month in file header: {month}")))?;
+// VFP tables end their header with the path to their database
+const BACKLINK_SIZE: u64 = 263;
 
-        let day = reader.read_u8()?;
+/// Everything we learn from the header, before the first record
+pub(crate) struct TableInfo {
+    pub(crate) header: Header,
+    pub(crate) code_page: CodePage,
+    pub(crate) fields: Vec<Field>,
+    pub(crate) properties: Vec<FieldProperty>,
+}
+
+/// Reads the header, fields and properties of a table starting at 0
+pub(crate) fn read_table_info<R: Read + Seek>(reader: &mut R) -> Result<TableInfo, Error> {
+    reader.seek(SeekFrom::Start(0))?;
+    let version = reader.read_u8()?;
+    let version = DbfVersion::from_repr(version)
+        .ok_or(FileFormat(format!("invalid file version: {version}")))?;
+
+    if version.is_level2() {
+        return read_level2_table_info(reader, version);
+    }
 
-        let last_update = Date::from_calendar_date(year, month, day)
-            .map_err(|_| FileFormat(format!("invalid date in header: {year}.{month}.{day}")))?;
+    let year = reader.read_u8()?;
+    let month = reader.read_u8()?;
+    let day = reader.read_u8()?;
+    let last_update = last_update(year, month, day)?;
 
-        let num_records = reader.read_u32::<LittleEndian>()?;
+    let num_records = reader.read_u32::<LittleEndian>()?;
 
-        let record_start = reader.read_u16::<LittleEndian>()?;
+    let record_start = reader.read_u16::<LittleEndian>()?;
 
-        let record_length = reader.read_u16::<LittleEndian>()?;
+    let record_length = reader.read_u16::<LittleEndian>()?;
 
-        let mut fields = Vec::new();
-        let mut loc = 0;
-        let mut offset = 1;
-        loop {
-            let pos = FIELD_START + FIELD_SIZE * loc;
-            reader.seek(SeekFrom::Start(pos))?;
+    // reserved, dBase IV uses the third and fourth for transactions and
+    // encryption and the last twelve for multi-user dBase
+    let mut reserved = [0u8; 16];
+    reader.read_exact(&mut reserved)?;
+    let incomplete_transaction = reserved[2] == 0x01;
+    let encrypted = reserved[3] == 0x01;
 
-            // maybe there are no more fields?
-            let terminator = reader.read_u8()?;
-            if terminator == 0x0d {
-                break;
-            }
+    let flags = reader.read_u8()?;
+    let language_driver = reader.read_u8()?;
 
-            reader.seek(SeekFrom::Start(pos))?;
-            let field = Field::new(&mut reader, offset)?;
-            offset += field.size();
-            fields.push(field);
+    let (field_start, field_size) = if version.is_level7() {
+        (LEVEL7_FIELD_START, LEVEL7_FIELD_SIZE)
+    } else {
+        (FIELD_START, FIELD_SIZE)
+    };
 
-            loc += 1;
+    let language_driver_name = if version.is_level7() {
+        reader.seek(SeekFrom::Current(2))?;
+        let mut name = [0u8; 32];
+        reader.read_exact(&mut name)?;
+        let name = String::from_utf8_lossy(name.until_terminator(&[0])).into_owned();
+        Some(name)
+    } else {
+        None
+    };
+
+    let mut fields = Vec::new();
+    let mut loc = 0;
+    let mut offset = 1;
+    let terminator_pos = loop {
+        let pos = field_start + field_size * loc;
+        reader.seek(SeekFrom::Start(pos))?;
+
+        // maybe there are no more fields?
+        let terminator = reader.read_u8()?;
+        if terminator == 0x0d {
+            break pos;
         }
 
-        let header = Header {
-            version,
-            last_update,
-            num_records,
-            record_start,
-            record_length,
+        reader.seek(SeekFrom::Start(pos))?;
+        let field = if version.is_level7() {
+            Field::new_level7(reader, offset)?
+        } else {
+            Field::new(reader, offset, version)?
         };
+        offset = offset
+            .checked_add(field.size())
+            .ok_or(FileFormat("fields are longer than a record can be".into()))?;
+        fields.push(field);
+
+        loc += 1;
+    };
+
+    check_record_length(offset, record_length)?;
+
+    // VFP puts system fields last but we make sure they stay there,
+    // then variable length fields get their bit in `_NullFlags`
+    // followed by another one when they are nullable, like Harbour does
+    fields.sort_by_key(|field| field.flags.is_system());
+    let mut bit = 0;
+    for field in fields.iter_mut() {
+        if matches!(
+            field.field_type,
+            FieldType::Varchar(_) | FieldType::Varbinary(_)
+        ) {
+            field.flags.length_bit = Some(bit);
+            bit += 1;
+        }
+        if field.flags.is_nullable() {
+            field.flags.null_bit = Some(bit);
+            bit += 1;
+        }
+    }
+
+    // and every one of those bits must be there
+    let null_flags = fields
+        .iter()
+        .find(|field| matches!(field.field_type, FieldType::NullFlags(_)))
+        .map_or(0, |field| field.length * 8);
+    if bit > null_flags {
+        return Err(FileFormat(format!(
+            "fields need {bit} bits in _NullFlags but there are {null_flags}"
+        )));
+    }
+
+    // dBase 7 may keep field properties between the fields and the records
+    let properties_size = (record_start as u64).saturating_sub(terminator_pos + 1);
+    let properties = if version.is_level7() && properties_size > 0 {
+        read_properties(reader, properties_size, &fields)?
+    } else {
+        Vec::new()
+    };
+
+    // VFP keeps the path to its database after the fields
+    let database = if version.is_visual_foxpro() {
+        reader.seek(SeekFrom::Start(terminator_pos + 1))?;
+        let mut backlink = Vec::with_capacity(BACKLINK_SIZE as usize);
+        reader
+            .by_ref()
+            .take(BACKLINK_SIZE)
+            .read_to_end(&mut backlink)?;
+        let backlink = String::from_utf8_lossy(backlink.until_terminator(&[0]));
+        Some(backlink.trim().to_string()).filter(|backlink| !backlink.is_empty())
+    } else {
+        None
+    };
+
+    let code_page = CodePage::from_language_driver(language_driver)
+        .or_else(|| {
+            language_driver_name
+                .as_deref()
+                .and_then(CodePage::from_language_driver_name)
+        })
+        .unwrap_or_default();
+
+    let header = Header {
+        version,
+        last_update,
+        num_records,
+        record_start,
+        record_length,
+        incomplete_transaction,
+        encrypted,
+        reserved,
+        flags,
+        language_driver,
+        language_driver_name,
+        database,
+    };
+
+    Ok(TableInfo {
+        header,
+        code_page,
+        fields,
+        properties,
+    })
+}
+
+/// Reads the header of a dBase II (or FoxBASE) table, its fields are
+/// smaller and records always start after room for 32 of them
+fn read_level2_table_info<R: Read + Seek>(
+    reader: &mut R,
+    version: DbfVersion,
+) -> Result<TableInfo, Error> {
+    let num_records = reader.read_u16::<LittleEndian>()? as u32;
+
+    let month = reader.read_u8()?;
+    let day = reader.read_u8()?;
+    let year = reader.read_u8()?;
+    let last_update = last_update(year, month, day)?;
+
+    let record_length = reader.read_u16::<LittleEndian>()?;
+
+    let mut fields = Vec::new();
+    let mut offset = 1;
+    for loc in 0..LEVEL2_MAX_FIELDS {
+        let pos = LEVEL2_FIELD_START + LEVEL2_FIELD_SIZE * loc;
+        reader.seek(SeekFrom::Start(pos))?;
+
+        // maybe there are no more fields?
+        let terminator = reader.read_u8()?;
+        if terminator == 0x0d {
+            break;
+        }
+
+        reader.seek(SeekFrom::Start(pos))?;
+        let field = Field::new_level2(reader, offset)?;
+        offset = offset
+            .checked_add(field.size())
+            .ok_or(FileFormat("fields are longer than a record can be".into()))?;
+        fields.push(field);
+    }
+
+    check_record_length(offset, record_length)?;
+
+    // dBase II had no language drivers, flags nor anything else
+    let header = Header {
+        version,
+        last_update,
+        num_records,
+        record_start: LEVEL2_HEADER_SIZE as u16,
+        record_length,
+        incomplete_transaction: false,
+        encrypted: false,
+        reserved: [0; 16],
+        flags: 0,
+        language_driver: 0,
+        language_driver_name: None,
+        database: None,
+    };
+
+    Ok(TableInfo {
+        header,
+        code_page: CodePage::default(),
+        fields,
+        properties: Vec::new(),
+    })
+}
+
+/// Date of the last update, stored as years since 1900
+fn last_update(year: u8, month: u8, day: u8) -> Result<Date, Error> {
+    let year = 1900 + (year as i32);
+    let month = Month::try_from(month)
+        .map_err(|_| FileFormat(format!("invalid month in file header: {month}")))?;
+
+    Date::from_calendar_date(year, month, day)
+        .map_err(|_| FileFormat(format!("invalid date in header: {year}.{month}.{day}")))
+}
+
+/// The deleted flag and every field must fill the record,
+/// otherwise we would read the fields from the wrong place
+fn check_record_length(offset: u16, record_length: u16) -> Result<(), Error> {
+    if offset != record_length {
+        return Err(FileFormat(format!(
+            "fields take {} bytes but records are {record_length} bytes long",
+            offset - 1
+        )));
+    }
+
+    Ok(())
+}
+
+/// Reads the header of a table that can't seek, the whole header is
+/// read at once so `reader` is left at the first record
+pub(crate) fn read_table_info_forward<R: Read>(reader: &mut R) -> Result<TableInfo, Error> {
+    let mut data = vec![0u8; HEADER_START_SIZE];
+    reader.read_exact(&mut data)?;
+
+    data.resize(header_size(&data)?, 0);
+    reader.read_exact(&mut data[HEADER_START_SIZE..])?;
+
+    read_table_info(&mut Cursor::new(data))
+}
+
+/// Bytes at the start of every table, enough to know how big its header is
+pub(crate) const HEADER_START_SIZE: usize = FIELD_START as usize;
+
+/// Size of the whole header from its first bytes, records start right after it
+pub(crate) fn header_size(start: &[u8]) -> Result<usize, Error> {
+    if DbfVersion::from_repr(start[0]).is_some_and(|version| version.is_level2()) {
+        return Ok(LEVEL2_HEADER_SIZE);
+    }
+
+    let record_start = u16::from_le_bytes([start[8], start[9]]) as usize;
+    if record_start < HEADER_START_SIZE {
+        return Err(FileFormat(format!(
+            "records can't start at {record_start}, inside the header"
+        )));
+    }
+
+    Ok(record_start)
+}
+
+impl<R: Read + Seek> DbfReader<R> {
+    /// Creates a DBF parser from a reader
+    pub fn from_reader(mut reader: R) -> Result<Self, Error> {
+        let table = read_table_info(&mut reader)?;
 
         Ok(Self {
             reader,
-            header,
+            header: table.header,
+            code_page: table.code_page,
             memo: None,
-            fields: Arc::new(fields),
+            fields: Arc::new(table.fields),
+            properties: table.properties,
+            filter: RowFilter::All,
+            memo_evidence: None,
         })
     }
 
     /// Sets a memo reader for memo fields
-    pub fn with_memo(mut self, memo: impl MemoRead + 'static) -> Self {
-        self.memo = Some(Box::new(memo));
+    pub fn with_memo(mut self, memo: impl MemoRead + Send + 'static) -> Self {
+        self.memo = Some(Arc::new(Mutex::new(memo)));
         self
     }
 
+    /// Table information from the header
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Guesses which program wrote this table, useful for 0x03 and
+    /// 0x83 tables where the version byte doesn't tell us. Tables opened
+    /// with `open` look at the header of their memo file as well
+    pub fn dialect(&self) -> DialectGuess {
+        guess(&self.header, &self.fields, self.memo_evidence)
+    }
+
+    /// Sets the code page used for text, overriding the one from the header,
+    /// tables without a language driver we know are read as CP437
+    pub fn with_code_page(mut self, code_page: CodePage) -> Self {
+        self.code_page = code_page;
+        self
+    }
+
+    /// Code page used for text in this table and its memos
+    pub fn code_page(&self) -> CodePage {
+        self.code_page
+    }
+
     /// Fields defined in this DBF table
+    /// system fields (like `_NullFlags` in VFP) are not included
     pub fn fields(&self) -> &[Field] {
+        visible_fields(&self.fields)
+    }
+
+    /// Every field defined in this DBF table, including system fields
+    pub fn all_fields(&self) -> &[Field] {
         &self.fields
     }
 
+    /// Renames the fields with the long names kept in the database (DBC),
+    /// they are in the same order as the fields in the table. When the
+    /// database has another number of fields it doesn't describe this table
+    /// and the fields keep their own names
+    pub fn with_long_names(mut self, table: &DatabaseTable) -> Self {
+        let fields = Arc::make_mut(&mut self.fields);
+        let end = visible_fields(fields).len();
+        if end != table.fields().len() {
+            return self;
+        }
+
+        for (field, long) in fields[..end].iter_mut().zip(table.fields()) {
+            field.name = long.name().to_string();
+        }
+        self
+    }
+
+    /// Finds a column by its name once, rows read it with `get_by_handle`
+    pub fn column(&self, name: &str) -> Result<ColumnIndex, Error> {
+        find_column(&self.fields, name)
+    }
+
+    /// Field properties (required, min, max, default and custom ones),
+    /// only dBase 7 tables have them
+    pub fn properties(&self) -> &[FieldProperty] {
+        &self.properties
+    }
+
+    /// Sets which rows we get depending on their deleted flag,
+    /// by default every row is returned
+    pub fn with_filter(mut self, filter: RowFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Which rows we get depending on their deleted flag
+    pub fn filter(&self) -> RowFilter {
+        self.filter
+    }
+
     /// Returns iterator to rows in the DBF table
-    /// this includes deleted rows
-    /// only one iterator at a time!
+    /// deleted rows are returned depending on `filter`
+    /// only one iterator at a time! files can use `partitions` instead
     #[must_use]
     pub fn rows(&mut self) -> Rows<'_, R> {
         Rows::new(
@@ -105,17 +441,194 @@ impl<R: Read + Seek> DbfReader<R> {
             self.header.record_start,
             self.header.num_records,
             Arc::clone(&self.fields),
+            self.memo.clone(),
+            self.code_page,
         )
+        .with_filter(self.filter)
+    }
+
+    /// Returns rows read in blocks into a reused buffer, faster than
+    /// `rows` for full scans, deleted rows depend on `filter` as well
+    #[must_use]
+    pub fn buffered_rows(&mut self) -> BufferedRows<'_, R> {
+        BufferedRows::new(
+            &mut self.reader,
+            self.header.record_length,
+            self.header.record_start,
+            self.header.num_records,
+            Arc::clone(&self.fields),
+            self.memo.clone(),
+            self.code_page,
+        )
+        .with_filter(self.filter)
+    }
+
+    /// Returns iterator to rows starting at the given record,
+    /// records are numbered from 0
+    pub fn rows_from(&mut self, record: u32) -> Result<Rows<'_, R>, Error> {
+        let total = self.header.num_records;
+        if record > total {
+            return Err(RecordOutOfBounds(record, total));
+        }
+
+        Ok(self.rows().starting_at(record))
+    }
+
+    /// Reads a single record even if it is deleted, records are numbered from 0
+    pub fn record(&mut self, record: u32) -> Result<Row, Error> {
+        let total = self.header.num_records;
+        self.rows_from(record)?
+            .with_filter(RowFilter::All)
+            .next()
+            .ok_or(RecordOutOfBounds(record, total))?
+    }
+}
+
+impl DbfReader<File> {
+    /// Opens a DBF table from a path, when the table has memo fields
+    /// its memo file (DBT or FPT) is found and attached as well
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut dbf = Self::from_reader(File::open(path)?)?;
+
+        if let Some((memo_path, layout)) = find_memo(path, dbf.header.version, &dbf.fields)? {
+            let mut memo = File::open(memo_path)?;
+            dbf.memo_evidence = MemoEvidence::from_reader(layout, &mut memo)?;
+            dbf.memo = Some(read_memo(layout, memo)?);
+        }
+
+        Ok(dbf)
     }
+
+    /// Splits the table in `count` partitions of consecutive records,
+    /// each one reads its rows on its own so they can go to different threads.
+    ///
+    /// Every partition shares the table's memo reader behind a `Mutex`,
+    /// so scans reading many memos mostly wait for each other
+    pub fn partitions(&self, count: usize) -> Vec<PartitionRows<'_>> {
+        split_records(self.header.num_records, count)
+            .into_iter()
+            .map(|records| {
+                PartitionRows::new(
+                    &self.reader,
+                    &self.header,
+                    records,
+                    Arc::clone(&self.fields),
+                    self.memo.clone(),
+                    self.code_page,
+                    self.filter,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Finds the memo file for a table and how it is laid out,
+/// `None` when the table has no memo fields
+pub(crate) fn find_memo(
+    path: &Path,
+    version: DbfVersion,
+    fields: &[Field],
+) -> Result<Option<(PathBuf, MemoLayout)>, Error> {
+    let has_memo = fields.iter().any(|field| field.field_type().is_memo());
+    if !has_memo {
+        return Ok(None);
+    }
+
+    let layout = version.memo_layout().ok_or(FileFormat(format!(
+        "memo fields are not expected in {version}"
+    )))?;
+
+    let extension = match layout {
+        MemoLayout::Dbt3 | MemoLayout::Dbt4 => "dbt",
+        // a database is a VFP table too, with its own memo extension
+        MemoLayout::Fpt if has_extension(path, "dbc") => "dct",
+        MemoLayout::Fpt => "fpt",
+        MemoLayout::Smt => return Err(smt_not_supported()),
+    };
+
+    let memo_path = find_companion(path, extension)?.ok_or(FileFormat(format!(
+        "memo file not found for {}",
+        path.display()
+    )))?;
+
+    Ok(Some((memo_path, layout)))
+}
+
+/// Reads a memo file with the reader for its layout
+pub(crate) fn read_memo<R: Read + Seek + Send + 'static>(
+    layout: MemoLayout,
+    reader: R,
+) -> Result<SharedMemo, Error> {
+    let memo: SharedMemo = match layout {
+        MemoLayout::Dbt3 => Arc::new(Mutex::new(Dbt3Reader::from_reader(reader)?)),
+        MemoLayout::Dbt4 => Arc::new(Mutex::new(Dbt4Reader::from_reader(reader)?)),
+        MemoLayout::Fpt => Arc::new(Mutex::new(FptReader::from_reader(reader)?)),
+        MemoLayout::Smt => return Err(smt_not_supported()),
+    };
+
+    Ok(memo)
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .and_then(|found| found.to_str())
+        .is_some_and(|found| found.eq_ignore_ascii_case(extension))
+}
+
+/// Looks for a file next to `path` with the same name but a different extension,
+/// DOS didn't care about case so neither do we
+fn find_companion(path: &Path, extension: &str) -> Result<Option<PathBuf>, Error> {
+    let candidate = path.with_extension(extension);
+    if candidate.is_file() {
+        return Ok(Some(candidate));
+    }
+
+    let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+        return Ok(None);
+    };
+    let expected = format!("{stem}.{extension}");
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    for entry in parent.read_dir()? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let matches = name
+            .to_str()
+            .is_some_and(|name| name.eq_ignore_ascii_case(&expected));
+        if matches && entry.file_type()?.is_file() {
+            return Ok(Some(entry.path()));
+        }
+    }
+
+    Ok(None)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::dbf::header::DbfVersion;
+    use crate::dbf::codepage::CodePage;
+    use crate::dbf::database::{DatabaseField, DatabaseTable};
+    use crate::dbf::dialect::Dialect;
+    use crate::dbf::fixtures::TableBuilder;
+    use crate::dbf::header::{DbfVersion, FieldType};
+    use crate::dbf::properties::PropertyKind;
     use crate::dbf::reader::DbfReader;
-    use crate::dbf::row::Value;
-    use crate::sample_file;
-    use time::{Date, Month};
+    use crate::dbf::rows::{RowFilter, Value};
+    use crate::dbf::stream::DbfStreamReader;
+    use crate::errors::Error::{
+        FieldNotFound, FileFormat, MemoNotFound, NotSupported, RecordOutOfBounds, UnexpectedType,
+    };
+    use crate::memo::MemoRead;
+    use crate::memo::dbt::{Dbt3Reader, Dbt4Reader};
+    use crate::memo::fpt::FptReader;
+    use crate::{sample_file, sample_path};
+    use rust_decimal::Decimal;
+    use std::io::{Cursor, Read};
+    use time::{Date, Month, PrimitiveDateTime, Time};
 
     #[test]
     fn dbase3_is_not_y2k_ready() -> anyhow::Result<()> {
@@ -225,6 +738,72 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn vfp_binary_fields() -> anyhow::Result<()> {
+        let dbf = DbfReader::from_reader(sample_file("vfp.dbf")?)?;
+
+        let binary: Vec<_> = dbf
+            .fields()
+            .iter()
+            .filter(|field| field.flags().is_binary())
+            .map(|field| field.name())
+            .collect();
+        assert_eq!(vec!["ID", "COST", "MARGIN", "UPDATED"], binary);
+
+        Ok(())
+    }
+
+    #[test]
+    fn code_page_from_language_driver() -> anyhow::Result<()> {
+        let dbf = DbfReader::from_reader(sample_file("vfp.dbf")?)?;
+        assert_eq!(0x03, dbf.header.language_driver);
+        assert_eq!(CodePage::Cp1252, dbf.code_page());
+
+        // no language driver, MS-DOS text unless we set one ourselves
+        let dbf = DbfReader::from_reader(sample_file("db3.dbf")?)?;
+        assert_eq!(0x00, dbf.header.language_driver);
+        assert_eq!(CodePage::Cp437, dbf.code_page());
+
+        let dbf = dbf.with_code_page(CodePage::Cp850);
+        assert_eq!(CodePage::Cp850, dbf.code_page());
+
+        // a language driver we don't know is read like a missing one
+        let mut data = vec![];
+        sample_file("db3.dbf")?.read_to_end(&mut data)?;
+        data[29] = 0xf0;
+        let name = 0xc1 + 0x2e * 7 + 1;
+        data[name..name + 6].copy_from_slice(b"Z\x81rich");
+        let mut dbf = DbfReader::from_reader(Cursor::new(data))?;
+        assert_eq!(0xf0, dbf.header().language_driver());
+        assert_eq!(CodePage::Cp437, dbf.code_page());
+        assert_eq!("Zürich", dbf.record(7)?.get_str("NAME")?.trim_end());
+
+        Ok(())
+    }
+
+    #[test]
+    fn public_header() -> anyhow::Result<()> {
+        let dbf = DbfReader::from_reader(sample_file("vfp.dbf")?)?;
+        let header = dbf.header();
+
+        assert_eq!(DbfVersion::VisualFoxPro, header.version());
+        assert_eq!(7, header.num_records());
+        assert_eq!(0x0268, header.record_start());
+        assert_eq!(0x54, header.record_length());
+        assert_eq!(0x03, header.language_driver());
+        assert!(!header.has_production_index());
+        assert!(!header.is_incomplete_transaction());
+        assert!(!header.is_encrypted());
+
+        // dBase 4 tells us its language driver too
+        let dbf = DbfReader::from_reader(sample_file("db4.dbf")?)?;
+        assert_eq!(0x19, dbf.header().language_driver());
+        assert_eq!(CodePage::Cp437, dbf.code_page());
+        assert_eq!(0x00, dbf.header().flags());
+
+        Ok(())
+    }
+
     #[test]
     fn read_field_types() -> anyhow::Result<()> {
         let mut reader = sample_file("db3.dbf")?;
@@ -274,4 +853,685 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn read_records_by_number() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::from_reader(sample_file("db3.dbf")?)?;
+
+        let row = dbf.record(2)?;
+        assert_eq!(
+            Value::Character("Thingamajig".to_string()),
+            row.get("NAME")?
+        );
+
+        // going back works as well
+        let row = dbf.record(0)?;
+        assert_eq!(Value::Character("Widget Pro".to_string()), row.get("NAME")?);
+
+        let mut records = vec![];
+        for row in dbf.rows_from(5)? {
+            records.push(row?.get("NAME")?);
+        }
+        let expected = vec![
+            Value::Character("Old Product".to_string()),
+            Value::Character("Broken Item".to_string()),
+            Value::Null,
+        ];
+        assert_eq!(expected, records);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_typed_columns() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::from_reader(sample_file("vfp.dbf")?)?;
+        let row = dbf.record(0)?;
+
+        let added = Date::from_calendar_date(1925, Month::January, 15)?;
+        assert_eq!(1, row.get_i32("ID")?);
+        assert_eq!("Widget Pro", row.get_str("NAME")?);
+        assert_eq!(Decimal::new(2999, 2), row.get_decimal("PRICE")?);
+        assert_eq!(150, row.get_i32("QTY")?);
+        assert!(row.get_bool("ACTIVE")?);
+        assert_eq!(added, row.get_date("ADDED")?);
+        assert_eq!(
+            PrimitiveDateTime::new(added, Time::from_hms(10, 30, 0)?),
+            row.get_datetime("UPDATED")?
+        );
+        assert_eq!(Some(added), row.get_date_opt("ADDED")?);
+
+        let error = row.get_date("NAME").unwrap_err();
+        assert!(matches!(error, UnexpectedType(_, "a date")));
+        assert_eq!("field NAME is not a date", error.to_string());
+
+        // nulls are only fine for the optional getters
+        let mut dbf = DbfReader::from_reader(sample_file("db3.dbf")?)?;
+        let row = dbf.record(7)?;
+        assert_eq!(None, row.get_str_opt("NAME")?);
+        assert!(matches!(
+            row.get_str("NAME"),
+            Err(UnexpectedType(_, "a string"))
+        ));
+        assert!(matches!(row.get_str_opt("MISSING"), Err(FieldNotFound(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_columns_by_handle() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::from_reader(sample_file("db3.dbf")?)?;
+        let name = dbf.column("name")?;
+        let position = dbf.fields().iter().position(|field| field.name() == "NAME");
+        let position = position.expect("NAME field");
+
+        assert!(matches!(dbf.column("missing"), Err(FieldNotFound(name)) if name == "missing"));
+
+        for row in dbf.rows() {
+            let row = row?;
+            assert_eq!(row.get("NAME")?, row.get_by_handle(name)?);
+            assert_eq!(row.get("NAME")?, row.get_by_index(position)?);
+            assert_eq!(name, row.column("Name")?);
+        }
+
+        let row = dbf.record(0)?;
+        assert!(row.get_by_index(dbf.fields().len()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn records_out_of_bounds() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::from_reader(sample_file("db3.dbf")?)?;
+
+        assert!(matches!(dbf.record(8), Err(RecordOutOfBounds(8, 8))));
+        assert_eq!(0, dbf.rows_from(8)?.count());
+        assert!(matches!(dbf.rows_from(9), Err(RecordOutOfBounds(9, 8))));
+
+        Ok(())
+    }
+
+    /// dBase table with `count` records numbered in a N6 field
+    fn numbered_table(count: u32) -> Vec<u8> {
+        let mut table = TableBuilder::new(0x03)
+            .with_last_update(124, 1, 1)
+            .with_numeric_field("ID", 6, 0);
+        for id in 0..count {
+            let deleted = if id % 3 == 0 { '*' } else { ' ' };
+            table = table.with_record(format!("{deleted}{id:>6}").as_bytes());
+        }
+
+        table.build()
+    }
+
+    #[test]
+    fn buffered_rows_match_rows() -> anyhow::Result<()> {
+        // enough records to need a few blocks
+        let mut dbf = DbfReader::from_reader(Cursor::new(numbered_table(25_000)))?;
+
+        let mut expected = vec![];
+        for row in dbf.rows() {
+            let row = row?;
+            expected.push((row.is_deleted(), row.get("ID")?));
+        }
+
+        let mut records = vec![];
+        let mut rows = dbf.buffered_rows();
+        while let Some(row) = rows.next_row() {
+            let row = row?;
+            records.push((row.is_deleted(), row.get("ID")?));
+        }
+
+        assert_eq!(25_000, records.len());
+        assert_eq!(expected, records);
+        assert_eq!(
+            (false, Value::Numeric(Decimal::from(24_998))),
+            records[24_998]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn filter_deleted_rows() -> anyhow::Result<()> {
+        let table = || Cursor::new(numbered_table(10));
+
+        let mut dbf = DbfReader::from_reader(table())?.with_filter(RowFilter::Live);
+        assert_eq!(RowFilter::Live, dbf.filter());
+        let mut records = vec![];
+        for row in dbf.rows() {
+            let row = row?;
+            assert!(!row.is_deleted());
+            records.push(row.record());
+        }
+        assert_eq!(vec![1, 2, 4, 5, 7, 8], records);
+
+        // random access doesn't care about the filter
+        assert!(dbf.record(3)?.is_deleted());
+
+        let mut dbf = DbfReader::from_reader(table())?.with_filter(RowFilter::Deleted);
+        let mut records = vec![];
+        let mut rows = dbf.buffered_rows();
+        while let Some(row) = rows.next_row() {
+            let row = row?;
+            assert!(row.is_deleted());
+            records.push(row.record());
+        }
+        assert_eq!(vec![0, 3, 6, 9], records);
+
+        // the filter can be changed for a single iterator
+        let mut dbf = DbfReader::from_reader(table())?;
+        assert_eq!(10, dbf.rows().count());
+        assert_eq!(4, dbf.rows().with_filter(RowFilter::Deleted).count());
+        let records: Vec<_> = dbf
+            .rows_from(6)?
+            .with_filter(RowFilter::Live)
+            .map(|row| row.map(|row| row.record()))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(vec![7, 8], records);
+
+        Ok(())
+    }
+
+    #[test]
+    fn buffered_rows_with_memo() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::open(sample_path("vfpmemo.dbf"))?;
+
+        let mut rows = dbf.buffered_rows();
+        let row = rows.next_row().expect("a row")?;
+        assert_eq!(
+            Value::Memo("Just a brief note.".to_string()),
+            row.get("MEMO")?
+        );
+
+        // rows may be kept once copied
+        let row = row.to_row();
+        assert!(rows.next_row().is_some());
+        assert_eq!(
+            Value::Memo("Just a brief note.".to_string()),
+            row.get("MEMO")?
+        );
+
+        Ok(())
+    }
+
+    fn read_memos(
+        table: &str,
+        column: &str,
+        memo: impl MemoRead + Send + 'static,
+    ) -> anyhow::Result<Vec<Value>> {
+        let mut dbf = DbfReader::from_reader(sample_file(table)?)?.with_memo(memo);
+
+        let mut records = vec![];
+        for row in dbf.rows() {
+            records.push(row?.get(column)?);
+        }
+
+        Ok(records)
+    }
+
+    #[test]
+    fn read_memo_fields() -> anyhow::Result<()> {
+        let expected = vec![
+            Value::Memo("Just a brief note.".to_string()),
+            Value::Memo(
+                "A much longer text that spans multiple blocks to test block boundary handling..."
+                    .to_string(),
+            ),
+            Value::Memo("First line.\r\nSecond line.\r\nThird line.".to_string()),
+            Value::Null,
+            Value::Memo("This was deleted.".to_string()),
+        ];
+
+        let memo = Dbt3Reader::from_reader(sample_file("db3memo.dbt")?)?;
+        assert_eq!(expected, read_memos("db3memo.dbf", "NOTES", memo)?);
+
+        // dBase 4 sample has an extra empty record at the end
+        let memo = Dbt4Reader::from_reader(sample_file("db4memo.dbt")?)?;
+        let records = read_memos("db4memo.dbf", "MEMO", memo)?;
+        assert_eq!(expected, records[..5]);
+        assert_eq!(Value::Null, records[5]);
+
+        let memo = Dbt4Reader::from_reader(sample_file("db5memo.dbt")?)?;
+        assert_eq!(expected, read_memos("db5memo.dbf", "MEMO", memo)?);
+
+        for (table, memo) in [
+            ("fox1memo.dbf", "fox1memo.fpt"),
+            ("fox2memo.dbf", "fox2memo.fpt"),
+            ("vfpmemo.dbf", "vfpmemo.fpt"),
+        ] {
+            let memo = FptReader::from_reader(sample_file(memo)?)?;
+            assert_eq!(expected, read_memos(table, "MEMO", memo)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn memo_fields_need_memo_file() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::from_reader(sample_file("db3memo.dbf")?)?;
+        let row = dbf.rows().next().expect("there is one row at least")?;
+
+        assert!(matches!(row.get("NOTES"), Err(MemoNotFound(name)) if name == "NOTES"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn open_attaches_memo() -> anyhow::Result<()> {
+        for (table, column) in [
+            ("db3memo.dbf", "NOTES"),
+            ("db4memo.dbf", "MEMO"),
+            ("db5memo.dbf", "MEMO"),
+            ("fox1memo.dbf", "MEMO"),
+            ("fox2memo.dbf", "MEMO"),
+            ("vfpmemo.dbf", "MEMO"),
+        ] {
+            let mut dbf = DbfReader::open(sample_path(table))?;
+            let row = dbf.rows().next().expect("there is one row at least")?;
+
+            assert_eq!(
+                Value::Memo("Just a brief note.".to_string()),
+                row.get(column)?
+            );
+        }
+
+        // tables without memo fields are fine on their own
+        let dbf = DbfReader::open(sample_path("vfp.dbf"))?;
+        assert!(dbf.memo.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn open_finds_memo_ignoring_case() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("dollop-case-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        std::fs::copy(sample_path("fox2memo.dbf"), dir.join("CUSTOMER.DBF"))?;
+        std::fs::copy(sample_path("fox2memo.fpt"), dir.join("Customer.Fpt"))?;
+
+        let dbf = DbfReader::open(dir.join("CUSTOMER.DBF"));
+        std::fs::remove_dir_all(&dir)?;
+
+        let mut dbf = dbf?;
+        let row = dbf.rows().next().expect("there is one row at least")?;
+        assert_eq!(
+            Value::Memo("Just a brief note.".to_string()),
+            row.get("MEMO")?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn open_without_memo_file_fails() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("dollop-missing-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        std::fs::copy(sample_path("db3memo.dbf"), dir.join("db3memo.dbf"))?;
+
+        let dbf = DbfReader::open(dir.join("db3memo.dbf"));
+        std::fs::remove_dir_all(&dir)?;
+
+        assert!(matches!(dbf, Err(FileFormat(_))));
+
+        Ok(())
+    }
+
+    /// dBase 7 table with a long field name, a default value and a caption
+    fn level7_table() -> Vec<u8> {
+        // properties: one standard (default for AMOUNT) and a custom one
+        let mut properties = vec![0x01, 0x00, 0x10, 0x00, 0x01, 0x00, 0x1f, 0x00];
+        properties.extend([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x48, 0x00]);
+        properties.extend([0x00, 0x00, 0x02, 0x00, 0x04, 0x02, 0x00, 0x00]);
+        properties.extend([0x00, 0x00, 0x00, 0x2d, 0x00, 0x0a, 0x00]);
+        properties.extend([0x00, 0x00, 0x02, 0x00, 0x01, 0x00, 0x37, 0x00]);
+        properties.extend([0x07, 0x00, 0x3e, 0x00, 0x0a, 0x00]);
+        properties.extend(b"      1.50CaptionAmount due");
+
+        TableBuilder::new(0x04)
+            .with_language_driver_name("DBWINUS0")
+            .with_field("CUSTOMER_NAME_LONGER", b'C', 20)
+            .with_numeric_field("AMOUNT", 10, 2)
+            .with_field("ID", b'I', 4)
+            .with_properties(&properties)
+            .with_record(
+                &[
+                    &b" Widget              "[..],
+                    b"     29.99",
+                    &[0x80, 0x00, 0x00, 0x07],
+                ]
+                .concat(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn read_level7_tables() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::from_reader(Cursor::new(level7_table()))?;
+
+        assert_eq!(DbfVersion::Dbase7, dbf.header().version());
+        assert_eq!(Some("DBWINUS0"), dbf.header().language_driver_name());
+        assert_eq!(CodePage::Cp1252, dbf.code_page());
+        assert_eq!(
+            Date::from_calendar_date(2026, Month::February, 17)?,
+            dbf.header().last_update()
+        );
+
+        let names: Vec<_> = dbf.fields().iter().map(|field| field.name()).collect();
+        assert_eq!(vec!["CUSTOMER_NAME_LONGER", "AMOUNT", "ID"], names);
+
+        let properties = dbf.properties();
+        assert_eq!(2, properties.len());
+        assert_eq!(Some("AMOUNT"), properties[0].field());
+        assert_eq!(&PropertyKind::Default, properties[0].kind());
+        assert_eq!(b"      1.50", properties[0].value());
+        assert_eq!(
+            &PropertyKind::Custom("Caption".to_string()),
+            properties[1].kind()
+        );
+        assert_eq!(b"Amount due", properties[1].value());
+
+        let row = dbf.rows().next().expect("there is one row")?;
+        assert_eq!(
+            Value::Character("Widget".to_string()),
+            row.get("customer_name_longer")?
+        );
+        assert_eq!(Value::Numeric(Decimal::new(2999, 2)), row.get("AMOUNT")?);
+
+        // dBase 7 longs are not VFP integers
+        assert!(matches!(dbf.fields()[2].field_type(), FieldType::Long));
+        assert_eq!(Value::Integer(7), row.get("ID")?);
+
+        Ok(())
+    }
+
+    /// VFP table with a nullable integer, so it has `_NullFlags`
+    fn nullable_table() -> Vec<u8> {
+        nullable_fields(TableBuilder::new(0x30), "ID", "NAME").build()
+    }
+
+    /// Adds a nullable integer and a character field to `table`, with
+    /// the records of [`nullable_table`]
+    fn nullable_fields(table: TableBuilder, id: &str, name: &str) -> TableBuilder {
+        table
+            .with_language_driver(0x03)
+            .with_flagged_field(id, b'I', 4, 0x06)
+            .with_field(name, b'C', 5)
+            .with_flagged_field("_NullFlags", b'0', 1, 0x05)
+            .with_record(&[&[0x20, 0x07, 0x00, 0x00, 0x00][..], b"Alpha", &[0x00]].concat())
+            .with_record(&[&[0x20, 0x00, 0x00, 0x00, 0x00][..], b"Beta ", &[0x01]].concat())
+    }
+
+    #[test]
+    fn vfp_null_flags() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::from_reader(Cursor::new(nullable_table()))?;
+
+        // _NullFlags is hidden unless we ask for it
+        let names: Vec<_> = dbf.fields().iter().map(|field| field.name()).collect();
+        assert_eq!(vec!["ID", "NAME"], names);
+        assert_eq!(3, dbf.all_fields().len());
+
+        let mut records = vec![];
+        for row in dbf.rows() {
+            let row = row?;
+            assert_eq!(2, row.fields().len());
+            records.push((row.get("ID")?, row.get("NAME")?));
+        }
+
+        let expected = vec![
+            (Value::Integer(7), Value::Character("Alpha".to_string())),
+            (Value::Null, Value::Character("Beta".to_string())),
+        ];
+        assert_eq!(expected, records);
+
+        Ok(())
+    }
+
+    /// VFP table with a nullable varchar followed by a nullable integer
+    fn varchar_table() -> Vec<u8> {
+        // NOTE's length bit comes first, then its null bit and ID's one
+        varchar_fields(b'0', 0x05)
+            .with_record(&[&b" ab\0\0\0\x02"[..], &7i32.to_le_bytes(), &[0b001]].concat())
+            .with_record(&[&[0x20][..], &[0x00; 10], &[0b110]].concat())
+            .with_record(&[&b" abcdef"[..], &9i32.to_le_bytes(), &[0b000]].concat())
+            .build()
+    }
+
+    /// A nullable varchar and a nullable integer, then the flags field
+    fn varchar_fields(flags_type: u8, flags: u8) -> TableBuilder {
+        TableBuilder::new(0x32)
+            .with_flagged_field("NOTE", b'V', 6, 0x02)
+            .with_flagged_field("ID", b'I', 4, 0x02)
+            .with_flagged_field("_NullFlags", flags_type, 1, flags)
+    }
+
+    #[test]
+    fn vfp_variable_length_flags() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::from_reader(Cursor::new(varchar_table()))?;
+
+        let flags = dbf.fields()[0].flags();
+        assert_eq!((Some(0), Some(1)), (flags.length_bit, flags.null_bit));
+        assert_eq!(Some(2), dbf.fields()[1].flags().null_bit);
+
+        let mut records = vec![];
+        for row in dbf.rows() {
+            let row = row?;
+            records.push((row.get("NOTE")?, row.get("ID")?));
+        }
+
+        let expected = vec![
+            (Value::Character("ab".to_string()), Value::Integer(7)),
+            (Value::Null, Value::Null),
+            (Value::Character("abcdef".to_string()), Value::Integer(9)),
+        ];
+        assert_eq!(expected, records);
+
+        Ok(())
+    }
+
+    #[test]
+    fn null_flags_must_hold_every_bit() {
+        // the flags turned into a plain character field
+        let data = varchar_fields(b'C', 0x00).build();
+
+        let result = DbfReader::from_reader(Cursor::new(data));
+        assert!(matches!(result, Err(FileFormat(message)) if message.contains("_NullFlags")));
+    }
+
+    #[test]
+    fn fields_must_fill_records() {
+        let mut data = nullable_table();
+        data[10] = 12;
+
+        let result = DbfReader::from_reader(Cursor::new(data));
+        assert!(matches!(result, Err(FileFormat(message)) if message.contains("10 bytes")));
+    }
+
+    #[test]
+    fn vfp_database_backlink() -> anyhow::Result<()> {
+        let dbf = DbfReader::from_reader(Cursor::new(nullable_table()))?;
+        assert_eq!(None, dbf.header().database());
+
+        // long names starting the same way get numbered short names
+        let table = TableBuilder::new(0x30).with_backlink("sales.dbc");
+        let data = nullable_fields(table, "CUSTOMER_N", "CUSTOMER_2").build();
+        let dbf = DbfReader::from_reader(Cursor::new(data))?;
+        assert_eq!(Some("sales.dbc"), dbf.header().database());
+
+        let long_names = |names: &[&str]| DatabaseTable {
+            name: "customers".to_string(),
+            fields: names
+                .iter()
+                .map(|name| DatabaseField {
+                    name: name.to_string(),
+                    ..DatabaseField::default()
+                })
+                .collect(),
+            ..DatabaseTable::default()
+        };
+
+        // a database describing another table renames nothing
+        let dbf = dbf.with_long_names(&long_names(&["customer_number"]));
+        let names: Vec<_> = dbf.all_fields().iter().map(|field| field.name()).collect();
+        assert_eq!(vec!["CUSTOMER_N", "CUSTOMER_2", "_NullFlags"], names);
+
+        let mut dbf = dbf.with_long_names(&long_names(&["customer_number", "customer_name"]));
+        let names: Vec<_> = dbf.all_fields().iter().map(|field| field.name()).collect();
+        assert_eq!(
+            vec!["customer_number", "customer_name", "_NullFlags"],
+            names
+        );
+
+        let row = dbf.rows().next().expect("a row")?;
+        assert_eq!(Value::Integer(7), row.get("CUSTOMER_NUMBER")?);
+        assert_eq!(
+            Value::Character("Alpha".to_string()),
+            row.get("customer_name")?
+        );
+
+        Ok(())
+    }
+
+    /// dBase II table, its descriptors are 16 bytes long and records
+    /// start after room for 32 of them
+    fn level2_table() -> Vec<u8> {
+        TableBuilder::new(0x02)
+            .with_last_update(84, 12, 31)
+            .with_field("NAME", b'C', 5)
+            .with_numeric_field("AMOUNT", 6, 2)
+            .with_record(b" Alpha  1.50")
+            .with_record(b"*Beta  -2.25")
+            .build()
+    }
+
+    #[test]
+    fn read_level2_tables() -> anyhow::Result<()> {
+        let mut dbf = DbfReader::from_reader(Cursor::new(level2_table()))?;
+
+        assert_eq!(DbfVersion::FoxBase, dbf.header().version());
+        assert_eq!(2, dbf.header().num_records());
+        assert_eq!(
+            Date::from_calendar_date(1984, Month::December, 31)?,
+            dbf.header().last_update()
+        );
+
+        let names: Vec<_> = dbf.fields().iter().map(|field| field.name()).collect();
+        assert_eq!(vec!["NAME", "AMOUNT"], names);
+
+        let rows = dbf
+            .rows()
+            .map(|row| {
+                let row = row?;
+                Ok((row.is_deleted(), row.get("NAME")?, row.get("AMOUNT")?))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let expected = vec![
+            (
+                false,
+                Value::Character("Alpha".to_string()),
+                Value::Numeric(Decimal::new(150, 2)),
+            ),
+            (
+                true,
+                Value::Character("Beta".to_string()),
+                Value::Numeric(Decimal::new(-225, 2)),
+            ),
+        ];
+        assert_eq!(expected, rows);
+
+        // streams need the header size before they read the fields
+        let mut stream = DbfStreamReader::from_reader(Cursor::new(level2_table()))?;
+        let row = stream.next().expect("a row")?;
+        assert_eq!(Value::Character("Alpha".to_string()), row.get("NAME")?);
+
+        Ok(())
+    }
+
+    /// Table laid out the dBase III way, most versions share it
+    fn dbase_table(version: u8) -> Vec<u8> {
+        TableBuilder::new(version)
+            .with_last_update(26, 2, 17)
+            .with_field("NAME", b'C', 5)
+            .with_record(b" Alpha")
+            .build()
+    }
+
+    /// Clipper table with a character field longer than 255 bytes
+    fn clipper_table() -> Vec<u8> {
+        TableBuilder::new(0xe5)
+            .with_field("NAME", b'C', 300)
+            .with_record(format!(" {:<300}", "Alpha").as_bytes())
+            .build()
+    }
+
+    #[test]
+    fn read_every_version() -> anyhow::Result<()> {
+        let mut tables = vec![
+            (level2_table(), Dialect::Dbase2),
+            (clipper_table(), Dialect::Clipper),
+        ];
+        for (version, dialect) in [
+            (0x03, Dialect::Dbase3),
+            (0x83, Dialect::Dbase3),
+            (0xfb, Dialect::FoxPro),
+            (0xf5, Dialect::FoxPro),
+            (0x8b, Dialect::Dbase4),
+            (0x43, Dialect::Dbase4),
+            (0x63, Dialect::Dbase4),
+            (0xb3, Dialect::Dbase4),
+            (0xcb, Dialect::Dbase4),
+        ] {
+            tables.push((dbase_table(version), dialect));
+        }
+        // VFP tables only change their version byte for what they hold
+        for version in [0x30, 0x31, 0x32] {
+            let data = nullable_fields(TableBuilder::new(version), "ID", "NAME").build();
+            tables.push((data, Dialect::VisualFoxPro));
+        }
+
+        for (data, dialect) in tables {
+            let version = data[0];
+            let mut dbf = DbfReader::from_reader(Cursor::new(data))?;
+            assert_eq!(version, dbf.header().version() as u8);
+            assert_eq!(dialect, dbf.dialect().dialect, "{version:#x}");
+
+            let row = dbf.rows().next().expect("a row")?;
+            assert_eq!(Value::Character("Alpha".to_string()), row.get("NAME")?);
+        }
+
+        // dBase 7 has its own layout too
+        let dbf = DbfReader::from_reader(Cursor::new(level7_table()))?;
+        assert_eq!(Dialect::Dbase7, dbf.dialect().dialect);
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_descriptors_fail_early() {
+        // a five bytes long date would panic when its row is read
+        let data = TableBuilder::new(0x03)
+            .with_field("ADDED", b'D', 5)
+            .with_record(b" 19250")
+            .build();
+
+        let result = DbfReader::from_reader(Cursor::new(data));
+        assert!(matches!(result, Err(FileFormat(message)) if message.contains("Date")));
+    }
+
+    #[test]
+    fn smt_memos_are_not_supported() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("dollop-smt-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+
+        let mut data = std::fs::read(sample_path("db3memo.dbf"))?;
+        data[0] = 0xe5;
+        std::fs::write(dir.join("clipper.dbf"), data)?;
+        std::fs::copy(sample_path("db3memo.dbt"), dir.join("clipper.smt"))?;
+
+        let dbf = DbfReader::open(dir.join("clipper.dbf"));
+        std::fs::remove_dir_all(&dir)?;
+
+        assert!(matches!(dbf, Err(NotSupported(what)) if what.contains("SMT")));
+
+        Ok(())
+    }
 }
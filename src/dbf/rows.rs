@@ -1,11 +1,15 @@
-use crate::dbf::header::{Field, FieldType};
+use crate::dbf::codepage::CodePage;
+use crate::dbf::convert::in_column;
+use crate::dbf::header::{Field, FieldType, visible_fields};
 use crate::errors::Error;
-use crate::errors::Error::{Fieldvalue, NotSupported};
-use byteorder::{LittleEndian, ReadBytesExt};
+use crate::errors::Error::{Fieldvalue, MemoNotFound, UnexpectedType};
+use crate::memo::{MemoType, SharedMemo, unpack_ole};
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
 use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError};
 use time::{Date, Month, PrimitiveDateTime, Time};
 
 /// A value contained in a field for a row
@@ -21,34 +25,333 @@ pub enum Value {
     Currency(Decimal),
     DateTime(time::PrimitiveDateTime),
     Double(f64),
+    Binary(Vec<u8>),
     Null,
 }
 
 /// Represent a row in a DBF file
 pub struct Row {
     fields: Arc<Vec<Field>>,
+    memo: Option<SharedMemo>,
+    code_page: CodePage,
+    record: u32,
     data: Vec<u8>,
 }
 
+/// A row borrowing its data, so reading it doesn't allocate
+#[derive(Clone, Copy)]
+pub struct RowRef<'a> {
+    fields: &'a Arc<Vec<Field>>,
+    memo: &'a Option<SharedMemo>,
+    code_page: CodePage,
+    record: u32,
+    data: &'a [u8],
+}
+
+const DELETED: u8 = 0x2a;
+
+/// Which rows we get when reading a table, depending on their deleted flag
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RowFilter {
+    /// Every row, deleted or not
+    #[default]
+    All,
+    /// Rows that are not deleted, like `SET DELETED ON`
+    Live,
+    /// Only deleted rows
+    Deleted,
+}
+
+impl RowFilter {
+    pub(crate) fn accepts(&self, flag: u8) -> bool {
+        match self {
+            RowFilter::All => true,
+            RowFilter::Live => flag != DELETED,
+            RowFilter::Deleted => flag == DELETED,
+        }
+    }
+}
+
+/// Getters converting a column to a type, with an `_opt` form
+/// returning `None` for nulls
+macro_rules! typed_getters {
+    ($($get:ident $get_opt:ident -> $ty:ty, $what:literal;)*) => {
+        $(
+            #[doc = concat!("Gets a column as ", $what, ", failing for any other value")]
+            pub fn $get(&self, column: &str) -> Result<$ty, Error> {
+                self.get_as(column)
+            }
+
+            #[doc = concat!("Gets a column as ", $what, ", or `None` when it is null")]
+            pub fn $get_opt(&self, column: &str) -> Result<Option<$ty>, Error> {
+                self.get_opt_as(column)
+            }
+        )*
+    };
+}
+
+macro_rules! row_getters {
+    () => {
+        typed_getters! {
+            get_str get_str_opt -> String, "text";
+            get_decimal get_decimal_opt -> Decimal, "a decimal";
+            get_i32 get_i32_opt -> i32, "an integer";
+            get_bool get_bool_opt -> bool, "a boolean";
+            get_date get_date_opt -> Date, "a date";
+            get_datetime get_datetime_opt -> PrimitiveDateTime, "a date time";
+        }
+    };
+}
+
+/// A column found by its name, reading it from a row is just indexing.
+/// It is only valid for rows of the table where it was found
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColumnIndex(pub(crate) usize);
+
+/// Finds a column ignoring case, system fields included
+pub(crate) fn find_column(fields: &[Field], name: &str) -> Result<ColumnIndex, Error> {
+    fields
+        .iter()
+        .position(|field| field.name().eq_ignore_ascii_case(name))
+        .map(ColumnIndex)
+        .ok_or(Error::FieldNotFound(name.to_string()))
+}
+
 #[inline]
 fn to_text(bytes: &[u8]) -> Result<&str, Error> {
     std::str::from_utf8(bytes).map_err(|_| Fieldvalue("invalid field value for text".into()))
 }
 
+/// Days between the julian day zero and the day before 0001-01-01
+const TIMESTAMP_JULIAN_DAY: u32 = 1_721_425;
+
+/// Builds a date time from julian days and milliseconds since midnight
+fn to_date_time(days: u32, millis: u32) -> Result<Value, Error> {
+    let date = Date::from_julian_day(days as i32)
+        .map_err(|_| Fieldvalue(format!("invalid days in gregorian: {days}")))?;
+
+    let hour = (millis / 3_600_000) as u8;
+    let min = ((millis % 3_600_000) / 60_000) as u8;
+    let sec = ((millis % 60_000) / 1_000) as u8;
+    let ms = (millis % 1_000) as u16;
+
+    let time = Time::from_hms_milli(hour, min, sec, ms)
+        .map_err(|_| Fieldvalue(format!("invalid time: {hour}:{min}:{sec}:{ms}")))?;
+
+    Ok(Value::DateTime(PrimitiveDateTime::new(date, time)))
+}
+
+/// dBase 7 flips the sign bit of positive doubles and every bit
+/// of negative ones, so we undo it to get the IEEE 754 bits back
+fn from_sortable(bits: u64) -> u64 {
+    if bits & 0x8000_0000_0000_0000 != 0 {
+        bits ^ 0x8000_0000_0000_0000
+    } else {
+        !bits
+    }
+}
+
+/// dBase word wraps DBT memos inserting a soft return (0x8d 0x0a),
+/// it is not part of the text so we drop it
+fn strip_soft_returns(mut bytes: Vec<u8>) -> Vec<u8> {
+    let mut pos = 0;
+    while let Some(found) = bytes[pos..].windows(2).position(|w| w == [0x8d, 0x0a]) {
+        pos += found;
+        bytes.drain(pos..pos + 2);
+    }
+    bytes
+}
+
 impl Row {
+    pub(crate) fn new(
+        fields: Arc<Vec<Field>>,
+        memo: Option<SharedMemo>,
+        code_page: CodePage,
+        record: u32,
+        data: Vec<u8>,
+    ) -> Self {
+        Self {
+            fields,
+            memo,
+            code_page,
+            record,
+            data,
+        }
+    }
+
     /// Tell us if the deleted flag is set for this record
     pub fn is_deleted(&self) -> bool {
-        self.data[0] == 0x2a
+        self.as_row_ref().is_deleted()
+    }
+
+    /// Returns the record number for this row, the first one is 0
+    pub fn record(&self) -> u32 {
+        self.record
     }
 
     /// Gets a column by its name
     pub fn get(&self, column: &str) -> Result<Value, Error> {
-        // first find field in list of fields
+        self.as_row_ref().get(column)
+    }
+
+    row_getters!();
+
+    /// Gets a column converting its value
+    pub fn get_as<T: TryFrom<Value, Error = Error>>(&self, column: &str) -> Result<T, Error> {
+        self.as_row_ref().get_as(column)
+    }
+
+    /// Gets a column converting its value, `None` when it is null
+    pub fn get_opt_as<T: TryFrom<Value, Error = Error>>(
+        &self,
+        column: &str,
+    ) -> Result<Option<T>, Error> {
+        self.as_row_ref().get_opt_as(column)
+    }
+
+    /// Gets a column by its position in `fields`
+    pub fn get_by_index(&self, index: usize) -> Result<Value, Error> {
+        self.as_row_ref().get_by_index(index)
+    }
+
+    /// Gets a column found before with `column`, without looking for its name
+    pub fn get_by_handle(&self, column: ColumnIndex) -> Result<Value, Error> {
+        self.as_row_ref().get_by_handle(column)
+    }
+
+    /// Finds a column by its name, so it can be read from every row
+    pub fn column(&self, name: &str) -> Result<ColumnIndex, Error> {
+        find_column(&self.fields, name)
+    }
+
+    /// Gets the block a memo column points to in its memo file without
+    /// reading it, `None` when there is no memo
+    pub fn memo_block(&self, column: &str) -> Result<Option<u32>, Error> {
+        self.as_row_ref().memo_block(column)
+    }
+
+    /// Returns fields in this row, without system fields
+    pub fn fields(&self) -> &[Field] {
+        visible_fields(&self.fields)
+    }
+
+    /// Deserializes this row into a struct, matching its fields with
+    /// the columns by name ignoring case, or into a tuple in field order
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        self.as_row_ref().deserialize()
+    }
+
+    /// Borrows this row
+    pub fn as_row_ref(&self) -> RowRef<'_> {
+        RowRef {
+            fields: &self.fields,
+            memo: &self.memo,
+            code_page: self.code_page,
+            record: self.record,
+            data: &self.data,
+        }
+    }
+}
+
+impl<'a> RowRef<'a> {
+    pub(crate) fn new(
+        fields: &'a Arc<Vec<Field>>,
+        memo: &'a Option<SharedMemo>,
+        code_page: CodePage,
+        record: u32,
+        data: &'a [u8],
+    ) -> Self {
+        Self {
+            fields,
+            memo,
+            code_page,
+            record,
+            data,
+        }
+    }
+
+    /// Tell us if the deleted flag is set for this record
+    pub fn is_deleted(&self) -> bool {
+        self.data[0] == DELETED
+    }
+
+    /// Returns the record number for this row, the first one is 0
+    pub fn record(&self) -> u32 {
+        self.record
+    }
+
+    /// Gets a column by its name
+    pub fn get(&self, column: &str) -> Result<Value, Error> {
+        let column = find_column(self.fields, column)?;
+        self.read(&self.fields[column.0])
+    }
+
+    row_getters!();
+
+    /// Gets a column converting its value
+    pub fn get_as<T: TryFrom<Value, Error = Error>>(&self, column: &str) -> Result<T, Error> {
+        T::try_from(self.get(column)?).map_err(|error| in_column(column, error))
+    }
+
+    /// Gets a column converting its value, `None` when it is null
+    pub fn get_opt_as<T: TryFrom<Value, Error = Error>>(
+        &self,
+        column: &str,
+    ) -> Result<Option<T>, Error> {
+        match self.get(column)? {
+            Value::Null => Ok(None),
+            value => T::try_from(value)
+                .map(Some)
+                .map_err(|error| in_column(column, error)),
+        }
+    }
+
+    /// Gets a column by its position in `fields`
+    pub fn get_by_index(&self, index: usize) -> Result<Value, Error> {
+        let field = self
+            .fields()
+            .get(index)
+            .ok_or(Error::FieldNotFound(format!("#{index}")))?;
+        self.read(field)
+    }
+
+    /// Gets a column found before with `column`, without looking for its name
+    pub fn get_by_handle(&self, column: ColumnIndex) -> Result<Value, Error> {
         let field = self
             .fields
-            .iter()
-            .find(|field| field.name().eq_ignore_ascii_case(column))
-            .ok_or(Error::FieldNotFound(column.to_string()))?;
+            .get(column.0)
+            .ok_or(Error::FieldNotFound(format!("#{}", column.0)))?;
+        self.read(field)
+    }
+
+    /// Finds a column by its name, so it can be read from every row
+    pub fn column(&self, name: &str) -> Result<ColumnIndex, Error> {
+        find_column(self.fields, name)
+    }
+
+    /// Gets the block a memo column points to in its memo file without
+    /// reading it, `None` when there is no memo
+    pub fn memo_block(&self, column: &str) -> Result<Option<u32>, Error> {
+        let field = &self.fields[find_column(self.fields, column)?.0];
+        if !field.field_type().is_memo() {
+            return Err(UnexpectedType(format!("field {column}"), "a memo"));
+        }
+
+        self.field_memo_block(field)
+    }
+
+    /// Same as `memo_block` for a field known to be a memo
+    pub(crate) fn field_memo_block(&self, field: &Field) -> Result<Option<u32>, Error> {
+        let start = field.offset as usize;
+        self.read_memo_block(start, start + field.size() as usize)
+    }
+
+    fn read(&self, field: &Field) -> Result<Value, Error> {
+        if let Some(bit) = field.flags.null_bit
+            && self.null_flag(bit)?
+        {
+            return Ok(Value::Null);
+        }
 
         let start = field.offset as usize;
         let end = (field.offset + field.size()) as usize;
@@ -58,7 +361,7 @@ impl Row {
                 if self.data[start..end].iter().all(|char| *char == 0x20) {
                     return Ok(Value::Null);
                 }
-                let text = to_text(&self.data[start..end])?;
+                let text = self.code_page.decode(&self.data[start..end])?;
                 let text = text.trim_ascii_end();
                 Value::Character(text.to_owned())
             }
@@ -106,7 +409,31 @@ impl Row {
 
                 Value::Date(date)
             }
-            FieldType::Memo => return Err(NotSupported),
+            FieldType::Memo => match self.read_memo(field, start, end)? {
+                // VFP memos created as binary are not text in any code page
+                Some((_, data)) if field.flags.is_binary() => Value::Binary(data),
+                Some((MemoType::Text, data)) if self.memo_has_soft_returns() => {
+                    let data = strip_soft_returns(data);
+                    Value::Memo(self.code_page.decode(&data)?.into_owned())
+                }
+                Some((MemoType::Text, data)) => {
+                    Value::Memo(self.code_page.decode(&data)?.into_owned())
+                }
+                Some((MemoType::Picture | MemoType::Object, data)) => Value::Binary(data),
+                None => Value::Null,
+            },
+            FieldType::Blob | FieldType::Picture => match self.read_memo(field, start, end)? {
+                Some((_, data)) => Value::Binary(data),
+                None => Value::Null,
+            },
+            FieldType::General => match self.read_memo(field, start, end)? {
+                // we want the document, not the OLE object wrapping it
+                Some((_, data)) => match unpack_ole(&data) {
+                    Some(document) => Value::Binary(document.to_vec()),
+                    None => Value::Binary(data),
+                },
+                None => Value::Null,
+            },
             // DBF4...
             FieldType::Float { .. } => {
                 if self.data[start..end].iter().all(|char| *char == 0x20) {
@@ -145,18 +472,7 @@ impl Row {
                     return Ok(Value::Null);
                 }
 
-                let date = Date::from_julian_day(days as i32)
-                    .map_err(|_| Fieldvalue(format!("invalid days in gregorian: {days}")))?;
-
-                let hour = (millis / 3_600_000) as u8;
-                let min = ((millis % 3_600_000) / 60_000) as u8;
-                let sec = ((millis % 60_000) / 1_000) as u8;
-                let ms = (millis % 1_000) as u16;
-
-                let time = Time::from_hms_milli(hour, min, sec, ms)
-                    .map_err(|_| Fieldvalue(format!("invalid time: {hour}:{min}:{sec}:{ms}")))?;
-
-                Value::DateTime(PrimitiveDateTime::new(date, time))
+                to_date_time(days, millis)?
             }
             FieldType::Double { .. } => {
                 let mut cursor = Cursor::new(&self.data[start..end]);
@@ -164,14 +480,169 @@ impl Row {
 
                 Value::Double(value)
             }
+            // dBase 7, numbers are big endian and sign flipped so they sort as bytes
+            FieldType::Long | FieldType::Autoincrement => {
+                let mut cursor = Cursor::new(&self.data[start..end]);
+                let value = cursor.read_u32::<BigEndian>()? ^ 0x8000_0000;
+
+                Value::Integer(value as i32)
+            }
+            FieldType::Dbase7Double => {
+                let mut cursor = Cursor::new(&self.data[start..end]);
+                let value = cursor.read_u64::<BigEndian>()?;
+                if value == 0 {
+                    return Ok(Value::Null);
+                }
+
+                Value::Double(f64::from_bits(from_sortable(value)))
+            }
+            FieldType::NullFlags(_) => Value::Binary(self.data[start..end].to_vec()),
+            FieldType::Varchar(_) => {
+                let bytes = self.variable_length(field, start, end)?;
+                Value::Character(self.code_page.decode(bytes)?.into_owned())
+            }
+            FieldType::Varbinary(_) => {
+                Value::Binary(self.variable_length(field, start, end)?.to_vec())
+            }
+            FieldType::Timestamp => {
+                // milliseconds since 0001-01-01 as a sortable double, like the BDE does
+                let mut cursor = Cursor::new(&self.data[start..end]);
+                let bits = cursor.read_u64::<BigEndian>()?;
+                if bits == 0 {
+                    return Ok(Value::Null);
+                }
+
+                let value = f64::from_bits(from_sortable(bits));
+                if !value.is_finite() || value < 0.0 {
+                    return Err(Fieldvalue(format!("invalid timestamp: {value}")));
+                }
+
+                let value = value as u64;
+                let days = u32::try_from(value / 86_400_000)
+                    .ok()
+                    .and_then(|days| days.checked_add(TIMESTAMP_JULIAN_DAY))
+                    .ok_or(Fieldvalue(format!("invalid timestamp: {value}")))?;
+                let millis = (value % 86_400_000) as u32;
+
+                to_date_time(days, millis)?
+            }
         };
 
         Ok(value)
     }
 
-    /// Returns fields in this row
-    pub fn fields(&self) -> &[Field] {
-        &self.fields
+    /// Returns fields in this row, without system fields
+    pub fn fields(&self) -> &'a [Field] {
+        visible_fields(self.fields)
+    }
+
+    /// Deserializes this row into a struct, matching its fields with
+    /// the columns by name ignoring case, or into a tuple in field order
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        T::deserialize(*self)
+    }
+
+    /// Copies the data so the row can outlive its buffer
+    pub fn to_row(&self) -> Row {
+        Row {
+            fields: Arc::clone(self.fields),
+            memo: self.memo.clone(),
+            code_page: self.code_page,
+            record: self.record,
+            data: self.data.to_vec(),
+        }
+    }
+
+    /// Reads the memo a field points to, `None` when there is no memo
+    fn read_memo(
+        &self,
+        field: &Field,
+        start: usize,
+        end: usize,
+    ) -> Result<Option<(MemoType, Vec<u8>)>, Error> {
+        let Some(block) = self.read_memo_block(start, end)? else {
+            return Ok(None);
+        };
+
+        let memo = self
+            .memo
+            .as_ref()
+            .ok_or(MemoNotFound(field.name().to_string()))?;
+        let memo = memo
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .read_typed_memo(block)?;
+
+        Ok(Some(memo))
+    }
+
+    /// Whether the memo file word wraps text with soft returns
+    fn memo_has_soft_returns(&self) -> bool {
+        self.memo.as_ref().is_some_and(|memo| {
+            memo.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .has_soft_returns()
+        })
+    }
+
+    /// Returns the block where a memo starts, `None` when there is no memo
+    fn read_memo_block(&self, start: usize, end: usize) -> Result<Option<u32>, Error> {
+        let block = match end - start {
+            // Visual FoxPro uses a binary integer
+            4 => {
+                let mut cursor = Cursor::new(&self.data[start..end]);
+                cursor.read_u32::<LittleEndian>()?
+            }
+            // while dBase and FoxPro use ASCII digits
+            _ => {
+                let text = to_text(&self.data[start..end])?.trim_ascii();
+                if text.is_empty() {
+                    return Ok(None);
+                }
+                text.parse::<u32>()
+                    .map_err(|_| Fieldvalue(format!("invalid memo block: {text}")))?
+            }
+        };
+
+        // block zero is the memo header, so there is no memo
+        Ok(Some(block).filter(|block| *block != 0))
+    }
+
+    /// Returns the bytes stored in a variable length field, when its
+    /// bit in `_NullFlags` is set the length is in the last byte
+    fn variable_length(&self, field: &Field, start: usize, end: usize) -> Result<&[u8], Error> {
+        let mut end = end;
+        if let Some(bit) = field.flags.length_bit
+            && self.null_flag(bit)?
+        {
+            let length = self.data[end - 1] as usize;
+            if length >= end - start {
+                return Err(Fieldvalue(format!(
+                    "invalid length for {}: {length}",
+                    field.name()
+                )));
+            }
+            end = start + length;
+        }
+
+        Ok(&self.data[start..end])
+    }
+
+    /// Reads a bit from the `_NullFlags` system field
+    fn null_flag(&self, bit: u16) -> Result<bool, Error> {
+        let field = self
+            .fields
+            .iter()
+            .find(|field| matches!(field.field_type, FieldType::NullFlags(_)))
+            .ok_or(Error::FieldNotFound("_NullFlags".to_string()))?;
+
+        let byte = field.offset as usize + (bit / 8) as usize;
+        let byte = self
+            .data
+            .get(byte)
+            .ok_or(Fieldvalue(format!("null flag {bit} is out of the record")))?;
+
+        Ok(byte & (1 << (bit % 8)) != 0)
     }
 }
 
@@ -180,6 +651,9 @@ pub struct Rows<'a, R: Read + Seek> {
     record_size: u16,
     record_start: u16,
     fields: Arc<Vec<Field>>,
+    memo: Option<SharedMemo>,
+    code_page: CodePage,
+    filter: RowFilter,
     current: u32,
     total: u32,
 }
@@ -191,54 +665,185 @@ impl<'a, R: Read + Seek> Rows<'a, R> {
         record_start: u16,
         total: u32,
         fields: Arc<Vec<Field>>,
+        memo: Option<SharedMemo>,
+        code_page: CodePage,
     ) -> Self {
         Self {
             reader,
             record_size,
             record_start,
             fields,
+            memo,
+            code_page,
+            filter: RowFilter::All,
             total,
             current: 0,
         }
     }
+
+    /// Moves to the given record, the next row read is that one
+    pub(crate) fn starting_at(mut self, record: u32) -> Self {
+        self.current = record;
+        self
+    }
+
+    /// Sets which rows we get depending on their deleted flag
+    pub fn with_filter(mut self, filter: RowFilter) -> Self {
+        self.filter = filter;
+        self
+    }
 }
 
 impl<'a, R: Read + Seek> Iterator for Rows<'a, R> {
     type Item = Result<Row, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current >= self.total {
-            return None;
+        loop {
+            if self.current >= self.total {
+                return None;
+            }
+            let record = self.current;
+            let position = (self.record_start as u64) + (self.record_size as u64) * (record as u64);
+            self.current += 1;
+            if let Err(err) = self.reader.seek(SeekFrom::Start(position)) {
+                return Some(Err(err.into()));
+            }
+
+            let mut data = vec![0u8; self.record_size as usize];
+            if let Err(err) = self.reader.read_exact(&mut data) {
+                return Some(Err(err.into()));
+            }
+
+            if !self.filter.accepts(data[0]) {
+                continue;
+            }
+
+            let row = Row {
+                fields: Arc::clone(&self.fields),
+                memo: self.memo.clone(),
+                code_page: self.code_page,
+                record,
+                data,
+            };
+
+            return Some(Ok(row));
         }
-        let position =
-            (self.record_start as u64) + (self.record_size as u64) * (self.current as u64);
-        self.current += 1;
-        if let Err(err) = self.reader.seek(SeekFrom::Start(position as u64)) {
-            return Some(Err(err.into()));
+    }
+}
+
+/// Bytes read at once by `BufferedRows`, rounded down to whole records
+pub(crate) const BLOCK_SIZE: usize = 64 * 1024;
+
+/// Rows read in blocks of many records into a buffer that is reused,
+/// each row borrows from it so only one can be alive at a time
+pub struct BufferedRows<'a, R: Read + Seek> {
+    reader: &'a mut R,
+    record_size: u16,
+    record_start: u16,
+    fields: Arc<Vec<Field>>,
+    memo: Option<SharedMemo>,
+    code_page: CodePage,
+    filter: RowFilter,
+    buffer: Vec<u8>,
+    position: usize,
+    current: u32,
+    total: u32,
+}
+
+impl<'a, R: Read + Seek> BufferedRows<'a, R> {
+    pub(crate) fn new(
+        reader: &'a mut R,
+        record_size: u16,
+        record_start: u16,
+        total: u32,
+        fields: Arc<Vec<Field>>,
+        memo: Option<SharedMemo>,
+        code_page: CodePage,
+    ) -> Self {
+        Self {
+            reader,
+            record_size,
+            record_start,
+            fields,
+            memo,
+            code_page,
+            filter: RowFilter::All,
+            buffer: Vec::new(),
+            position: 0,
+            current: 0,
+            total,
         }
+    }
+
+    /// Sets which rows we get depending on their deleted flag
+    pub fn with_filter(mut self, filter: RowFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Returns the next row, it can't outlive the next call.
+    /// When reading a block fails there are no more rows
+    pub fn next_row(&mut self) -> Option<Result<RowRef<'_>, Error>> {
+        loop {
+            if self.current >= self.total {
+                return None;
+            }
+
+            if self.position >= self.buffer.len()
+                && let Err(err) = self.read_block()
+            {
+                self.current = self.total;
+                return Some(Err(err));
+            }
 
-        let mut data = vec![0u8; self.record_size as usize];
-        if let Err(err) = self.reader.read_exact(&mut data) {
-            return Some(Err(err.into()));
+            let start = self.position;
+            let record = self.current;
+            self.position += self.record_size as usize;
+            self.current += 1;
+
+            if self.filter.accepts(self.buffer[start]) {
+                return Some(Ok(RowRef {
+                    fields: &self.fields,
+                    memo: &self.memo,
+                    code_page: self.code_page,
+                    record,
+                    data: &self.buffer[start..self.position],
+                }));
+            }
         }
+    }
 
-        let row = Row {
-            fields: Arc::clone(&self.fields),
-            data,
-        };
+    fn read_block(&mut self) -> Result<(), Error> {
+        let record_size = self.record_size as usize;
+        let records = (BLOCK_SIZE / record_size)
+            .max(1)
+            .min((self.total - self.current) as usize);
+
+        let position =
+            (self.record_start as u64) + (self.record_size as u64) * (self.current as u64);
+        self.reader.seek(SeekFrom::Start(position))?;
+
+        self.buffer.resize(records * record_size, 0);
+        self.reader.read_exact(&mut self.buffer)?;
+        self.position = 0;
 
-        Some(Ok(row))
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::dbf::header::{Field, FieldType};
+    use crate::dbf::codepage::CodePage;
+    use crate::dbf::header::{Field, FieldFlags, FieldType};
     use crate::dbf::rows::{Row, Value};
+    use crate::errors::Error::Fieldvalue;
+    use crate::memo::dbt::Dbt3Reader;
+    use crate::memo::fpt::FptReader;
     use rust_decimal::Decimal;
     use rust_decimal::prelude::FromPrimitive;
+    use std::io::Cursor;
     use std::str::FromStr;
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
     use time::{Date, Month, PrimitiveDateTime, Time};
 
     #[test]
@@ -247,33 +852,53 @@ mod tests {
             Field {
                 name: "NAME".to_string(),
                 offset: 1,
+                length: 20,
+                decimal: 0,
                 field_type: FieldType::Character(20),
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "PRICE".to_string(),
                 offset: 21,
+                length: 10,
+                decimal: 2,
                 field_type: FieldType::Numeric {
                     size: 10,
                     decimal: 2,
                 },
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "QTY".to_string(),
                 offset: 31,
+                length: 6,
+                decimal: 0,
                 field_type: FieldType::Numeric {
                     size: 6,
                     decimal: 0,
                 },
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "ACTIVE".to_string(),
                 offset: 37,
+                length: 1,
+                decimal: 0,
                 field_type: FieldType::Logical,
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "ADDED".to_string(),
                 offset: 38,
+                length: 8,
+                decimal: 0,
                 field_type: FieldType::Date,
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
         ];
         let data: [u8; 0x2E] = [
@@ -285,6 +910,9 @@ mod tests {
 
         let row = Row {
             fields: Arc::new(fields),
+            memo: None,
+            code_page: CodePage::Utf8,
+            record: 0,
             data: data.to_vec(),
         };
 
@@ -339,66 +967,109 @@ mod tests {
             Field {
                 name: "ID".to_string(),
                 offset: 1,
+                length: 4,
+                decimal: 0,
                 field_type: FieldType::Integer,
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "NAME".to_string(),
                 offset: 5,
+                length: 20,
+                decimal: 0,
                 field_type: FieldType::Character(20),
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "PRICE".to_string(),
                 offset: 25,
+                length: 10,
+                decimal: 2,
                 field_type: FieldType::Numeric {
                     size: 10,
                     decimal: 2,
                 },
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "COST".to_string(),
                 offset: 35,
+                length: 8,
+                decimal: 0,
                 field_type: FieldType::Currency,
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "QTY".to_string(),
                 offset: 43,
+                length: 6,
+                decimal: 0,
                 field_type: FieldType::Numeric {
                     size: 6,
                     decimal: 0,
                 },
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "WEIGHT".to_string(),
                 offset: 49,
+                length: 6,
+                decimal: 0,
                 field_type: FieldType::Numeric {
                     size: 6,
                     decimal: 0,
                 },
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "MARGIN".to_string(),
                 offset: 59,
+                length: 8,
+                decimal: 0,
                 field_type: FieldType::Double { decimal: 4 },
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "ACTIVE".to_string(),
                 offset: 67,
+                length: 1,
+                decimal: 0,
                 field_type: FieldType::Logical,
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "ADDED".to_string(),
                 offset: 68,
+                length: 8,
+                decimal: 0,
                 field_type: FieldType::Date,
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
             Field {
                 name: "UPDATED".to_string(),
                 offset: 76,
+                length: 8,
+                decimal: 0,
                 field_type: FieldType::DateTime,
+                displacement: 0,
+                flags: FieldFlags::default(),
             },
         ];
 
         let row = Row {
             fields: Arc::new(fields),
+            memo: None,
+            code_page: CodePage::Utf8,
+            record: 0,
             data: data.to_vec(),
         };
 
@@ -423,4 +1094,294 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn binary_memos_are_bytes() -> anyhow::Result<()> {
+        // FPT with 64 bytes blocks and a picture stored in block 8
+        let mut memo = vec![0u8; 512];
+        memo[..8].copy_from_slice(&[0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x40]);
+        memo.extend([
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xff, 0xd8, 0xff,
+        ]);
+        let memo = FptReader::from_reader(Cursor::new(memo))?;
+
+        let fields = vec![Field {
+            name: "PHOTO".to_string(),
+            offset: 1,
+            length: 4,
+            decimal: 0,
+            field_type: FieldType::Memo,
+            displacement: 0,
+            flags: FieldFlags::default(),
+        }];
+
+        let row = Row {
+            fields: Arc::new(fields),
+            memo: Some(Arc::new(Mutex::new(memo))),
+            code_page: CodePage::Utf8,
+            record: 0,
+            data: vec![0x20, 0x08, 0x00, 0x00, 0x00],
+        };
+
+        assert_eq!(Value::Binary(vec![0xff, 0xd8, 0xff]), row.get("PHOTO")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn soft_returns_only_in_dbt_memos() -> anyhow::Result<()> {
+        let fields = vec![Field {
+            name: "NOTES".to_string(),
+            offset: 1,
+            length: 10,
+            decimal: 0,
+            field_type: FieldType::Memo,
+            displacement: 0,
+            flags: FieldFlags::default(),
+        }];
+
+        // dBase III wrapped the memo in block 1
+        let mut dbt = vec![0u8; 512];
+        dbt[..4].copy_from_slice(&2u32.to_le_bytes());
+        dbt.extend(b"soft \x8d\x0areturn\x1a\x1a");
+        let row = Row {
+            fields: Arc::new(fields),
+            memo: Some(Arc::new(Mutex::new(Dbt3Reader::from_reader(Cursor::new(
+                dbt,
+            ))?))),
+            code_page: CodePage::Cp850,
+            record: 0,
+            data: b"          1".to_vec(),
+        };
+        assert_eq!(Value::Memo("soft return".to_string()), row.get("NOTES")?);
+
+        // FPT with 64 bytes blocks, 0x8d is a character in code page 850
+        let mut fpt = vec![0u8; 512];
+        fpt[..8].copy_from_slice(&[0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x40]);
+        fpt.extend([0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x04]);
+        fpt.extend(b"Pi\x8d\x0a");
+        let row = Row {
+            memo: Some(Arc::new(Mutex::new(FptReader::from_reader(Cursor::new(
+                fpt,
+            ))?))),
+            data: b"          8".to_vec(),
+            ..row
+        };
+        assert_eq!(Value::Memo("Piì\n".to_string()), row.get("NOTES")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_rows_vfp_binary_memos() -> anyhow::Result<()> {
+        // embedded OLE object, a picture with nothing for topic and item
+        let mut ole = vec![0x01, 0x05, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00];
+        ole.extend([0x07, 0x00, 0x00, 0x00]);
+        ole.extend(b"PBrush\0");
+        ole.extend([0x00; 8]);
+        ole.extend([0x02, 0x00, 0x00, 0x00, b'B', b'M']);
+
+        // FPT with 64 bytes blocks, a blob in block 8 and the OLE object in 9
+        let mut memo = vec![0u8; 512];
+        memo[..8].copy_from_slice(&[0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x40]);
+        for (record_type, content) in [(1u32, &[0x00, 0x01, 0x02][..]), (2, &ole)] {
+            let mut block = vec![0u8; 64];
+            block[..4].copy_from_slice(&record_type.to_be_bytes());
+            block[4..8].copy_from_slice(&(content.len() as u32).to_be_bytes());
+            block[8..8 + content.len()].copy_from_slice(content);
+            memo.extend(block);
+        }
+        let memo = FptReader::from_reader(Cursor::new(memo))?;
+
+        let field = |name: &str, offset, field_type| Field {
+            name: name.to_string(),
+            offset,
+            length: 4,
+            decimal: 0,
+            field_type,
+            displacement: offset as u32,
+            flags: FieldFlags::default(),
+        };
+        let fields = vec![
+            field("DATA", 1, FieldType::Blob),
+            field("DOC", 5, FieldType::General),
+            field("LOGO", 9, FieldType::Picture),
+        ];
+
+        let mut data = vec![0x20];
+        data.extend(8u32.to_le_bytes());
+        data.extend(9u32.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+
+        let row = Row {
+            fields: Arc::new(fields),
+            memo: Some(Arc::new(Mutex::new(memo))),
+            code_page: CodePage::Utf8,
+            record: 0,
+            data,
+        };
+
+        assert_eq!(Value::Binary(vec![0x00, 0x01, 0x02]), row.get("DATA")?);
+        assert_eq!(Value::Binary(b"BM".to_vec()), row.get("DOC")?);
+        assert_eq!(Value::Null, row.get("LOGO")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn text_uses_code_page() -> anyhow::Result<()> {
+        let fields = vec![Field {
+            name: "CITY".to_string(),
+            offset: 1,
+            length: 8,
+            decimal: 0,
+            field_type: FieldType::Character(8),
+            displacement: 0,
+            flags: FieldFlags::default(),
+        }];
+        let data = b" Z\x81rich  ".to_vec();
+
+        let row = Row {
+            fields: Arc::new(fields),
+            memo: None,
+            code_page: CodePage::Cp850,
+            record: 0,
+            data,
+        };
+        assert_eq!(Value::Character("Zürich".to_string()), row.get("CITY")?);
+
+        // same bytes are not valid UTF-8
+        let row = Row {
+            code_page: CodePage::Utf8,
+            record: 0,
+            ..row
+        };
+        assert!(row.get("CITY").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_rows_dbase7() -> anyhow::Result<()> {
+        let date = Date::from_calendar_date(1925, Month::January, 15)?;
+        let millis = (date.to_julian_day() - 1_721_425) as f64 * 86_400_000.0 + 37_800_000.0;
+
+        let mut data = vec![0x20];
+        data.extend([0x80, 0x00, 0x00, 0x05]);
+        data.extend([0x7f, 0xff, 0xff, 0xff]);
+        data.extend([0xbf, 0xe0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        data.extend([0x3f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        // timestamps are sortable doubles too, positive ones have the sign bit set
+        data.extend((millis.to_bits() | 0x8000_0000_0000_0000).to_be_bytes());
+        // the largest double, flipped, is far past any date
+        data.extend((f64::MAX.to_bits() | 0x8000_0000_0000_0000).to_be_bytes());
+
+        let fields = [
+            ("ID", 1, 4, FieldType::Autoincrement),
+            ("DELTA", 5, 4, FieldType::Long),
+            ("HALF", 9, 8, FieldType::Dbase7Double),
+            ("MINUS", 17, 8, FieldType::Dbase7Double),
+            ("UPDATED", 25, 8, FieldType::Timestamp),
+            ("BROKEN", 33, 8, FieldType::Timestamp),
+        ]
+        .into_iter()
+        .map(|(name, offset, length, field_type)| Field {
+            name: name.to_string(),
+            offset,
+            length,
+            decimal: 0,
+            field_type,
+            displacement: 0,
+            flags: FieldFlags::default(),
+        })
+        .collect();
+
+        let row = Row {
+            fields: Arc::new(fields),
+            memo: None,
+            code_page: CodePage::Utf8,
+            record: 0,
+            data,
+        };
+
+        assert_eq!(Value::Integer(5), row.get("ID")?);
+        assert_eq!(Value::Integer(-1), row.get("DELTA")?);
+        assert_eq!(Value::Double(0.5), row.get("HALF")?);
+        assert_eq!(Value::Double(-2.0), row.get("MINUS")?);
+
+        let time = Time::from_hms(10, 30, 0)?;
+        assert_eq!(
+            Value::DateTime(PrimitiveDateTime::new(date, time)),
+            row.get("UPDATED")?
+        );
+        assert!(matches!(row.get("BROKEN"), Err(Fieldvalue(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_rows_varchar() -> anyhow::Result<()> {
+        let fields = vec![
+            Field {
+                name: "NOTE".to_string(),
+                offset: 1,
+                length: 6,
+                decimal: 0,
+                field_type: FieldType::Varchar(6),
+                displacement: 1,
+                flags: FieldFlags {
+                    length_bit: Some(0),
+                    ..FieldFlags::default()
+                },
+            },
+            Field {
+                name: "DATA".to_string(),
+                offset: 7,
+                length: 4,
+                decimal: 0,
+                field_type: FieldType::Varbinary(4),
+                displacement: 7,
+                flags: FieldFlags {
+                    bits: 0x06,
+                    null_bit: Some(2),
+                    length_bit: Some(1),
+                    ..FieldFlags::default()
+                },
+            },
+            Field {
+                name: "_NullFlags".to_string(),
+                offset: 11,
+                length: 1,
+                decimal: 0,
+                field_type: FieldType::NullFlags(1),
+                displacement: 11,
+                flags: FieldFlags {
+                    bits: 0x05,
+                    ..FieldFlags::default()
+                },
+            },
+        ];
+        let row = |data: &[u8]| Row {
+            fields: Arc::new(fields.clone()),
+            memo: None,
+            code_page: CodePage::Utf8,
+            record: 0,
+            data: data.to_vec(),
+        };
+
+        // shorter values keep their length in the last byte
+        let short = row(b" abc\0\0\x03\x01\x02\x03\x04\x01");
+        assert_eq!(Value::Character("abc".to_string()), short.get("NOTE")?);
+        assert_eq!(Value::Binary(vec![1, 2, 3, 4]), short.get("DATA")?);
+
+        let null = row(b" abcdef\0\0\0\0\x04");
+        assert_eq!(Value::Character("abcdef".to_string()), null.get("NOTE")?);
+        assert_eq!(Value::Null, null.get("DATA")?);
+
+        let binary = row(b" ab  \0\x02\x0a\x0b\0\x02\x03");
+        assert_eq!(Value::Character("ab".to_string()), binary.get("NOTE")?);
+        assert_eq!(Value::Binary(vec![0x0a, 0x0b]), binary.get("DATA")?);
+
+        Ok(())
+    }
 }
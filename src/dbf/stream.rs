@@ -0,0 +1,200 @@
+use crate::dbf::codepage::CodePage;
+use crate::dbf::header::{Field, Header, visible_fields};
+use crate::dbf::reader::read_table_info_forward;
+use crate::dbf::rows::{ColumnIndex, Row, RowFilter, find_column};
+use crate::errors::Error;
+use std::io::Read;
+use std::sync::Arc;
+
+/// A DBF table reader for streams that can't seek (stdin, sockets,
+/// decompressors), the header is read first and then every record in order.
+///
+/// There is no memo file to follow, memo columns fail with `MemoNotFound`
+/// when read, use `memo_block` to get where they point to instead
+pub struct DbfStreamReader<R: Read> {
+    reader: R,
+    header: Header,
+    code_page: CodePage,
+    fields: Arc<Vec<Field>>,
+    filter: RowFilter,
+    current: u32,
+    failed: bool,
+}
+
+impl<R: Read> DbfStreamReader<R> {
+    /// Creates a DBF parser from a stream positioned at the start of a table,
+    /// it is left at the first record
+    pub fn from_reader(mut reader: R) -> Result<Self, Error> {
+        let table = read_table_info_forward(&mut reader)?;
+
+        Ok(Self {
+            reader,
+            header: table.header,
+            code_page: table.code_page,
+            fields: Arc::new(table.fields),
+            filter: RowFilter::All,
+            current: 0,
+            failed: false,
+        })
+    }
+
+    /// Table information from the header
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Sets the code page used for text, overriding the one from the header,
+    /// tables without a language driver we know are read as CP437
+    pub fn with_code_page(mut self, code_page: CodePage) -> Self {
+        self.code_page = code_page;
+        self
+    }
+
+    /// Code page used for text in this table
+    pub fn code_page(&self) -> CodePage {
+        self.code_page
+    }
+
+    /// Fields defined in this DBF table
+    /// system fields (like `_NullFlags` in VFP) are not included
+    pub fn fields(&self) -> &[Field] {
+        visible_fields(&self.fields)
+    }
+
+    /// Every field defined in this DBF table, including system fields
+    pub fn all_fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Finds a column by its name once, rows read it with `get_by_handle`
+    pub fn column(&self, name: &str) -> Result<ColumnIndex, Error> {
+        find_column(&self.fields, name)
+    }
+
+    /// Sets which rows we get depending on their deleted flag,
+    /// by default every row is returned
+    pub fn with_filter(mut self, filter: RowFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Which rows we get depending on their deleted flag
+    pub fn filter(&self) -> RowFilter {
+        self.filter
+    }
+}
+
+/// Rows in record order, records already read can't be read again
+impl<R: Read> Iterator for DbfStreamReader<R> {
+    type Item = Result<Row, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // after an error we don't know where the next record is
+            if self.failed || self.current >= self.header.num_records {
+                return None;
+            }
+            let record = self.current;
+            self.current += 1;
+
+            let mut data = vec![0u8; self.header.record_length as usize];
+            if let Err(err) = self.reader.read_exact(&mut data) {
+                self.failed = true;
+                return Some(Err(err.into()));
+            }
+
+            if !self.filter.accepts(data[0]) {
+                continue;
+            }
+
+            let row = Row::new(Arc::clone(&self.fields), None, self.code_page, record, data);
+
+            return Some(Ok(row));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dbf::reader::DbfReader;
+    use crate::dbf::rows::{RowFilter, Value};
+    use crate::dbf::stream::DbfStreamReader;
+    use crate::errors::Error::{Io, MemoNotFound};
+    use crate::sample_file;
+    use std::io::{Read, Result as IOResult};
+
+    /// Hands out a few bytes at a time and can't seek, like a pipe
+    struct Pipe<R: Read>(R);
+
+    impl<R: Read> Read for Pipe<R> {
+        fn read(&mut self, buf: &mut [u8]) -> IOResult<usize> {
+            let size = buf.len().min(7);
+            self.0.read(&mut buf[..size])
+        }
+    }
+
+    #[test]
+    fn stream_rows_match_rows() -> anyhow::Result<()> {
+        for name in ["db3.dbf", "vfp.dbf", "db4.dbf"] {
+            let mut dbf = DbfReader::from_reader(sample_file(name)?)?;
+            let stream = DbfStreamReader::from_reader(Pipe(sample_file(name)?))?;
+            assert_eq!(dbf.fields().len(), stream.fields().len());
+
+            let mut count = 0;
+            for (expected, row) in dbf.rows().zip(stream) {
+                let (expected, row) = (expected?, row?);
+                assert_eq!(expected.record(), row.record());
+                assert_eq!(expected.is_deleted(), row.is_deleted());
+                assert_eq!(expected.get("NAME")?, row.get("NAME")?);
+                count += 1;
+            }
+            assert_eq!(dbf.header().num_records(), count);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn stream_filtered_rows() -> anyhow::Result<()> {
+        let stream = DbfStreamReader::from_reader(Pipe(sample_file("db3.dbf")?))?;
+        let stream = stream.with_filter(RowFilter::Deleted);
+
+        let names = stream
+            .map(|row| Ok(row?.get("NAME")?))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let expected = vec![
+            Value::Character("Thingamajig".to_string()),
+            Value::Character("Old Product".to_string()),
+        ];
+        assert_eq!(expected, names);
+
+        Ok(())
+    }
+
+    #[test]
+    fn stream_memo_blocks() -> anyhow::Result<()> {
+        let mut stream = DbfStreamReader::from_reader(Pipe(sample_file("vfpmemo.dbf")?))?;
+        let row = stream.next().unwrap()?;
+
+        assert!(matches!(row.get("MEMO"), Err(MemoNotFound(_))));
+        assert!(row.memo_block("MEMO")?.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn stream_ends_early() -> anyhow::Result<()> {
+        let mut data = vec![];
+        sample_file("db3.dbf")?.read_to_end(&mut data)?;
+        data.truncate(data.len() - 60);
+
+        let stream = DbfStreamReader::from_reader(&data[..])?;
+        let rows = stream.collect::<Vec<_>>();
+
+        // the last records are missing, we don't go on after that
+        assert_eq!(7, rows.len());
+        assert!(matches!(rows.last(), Some(Err(Io(_)))));
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use std::fmt::Display;
 use std::io;
 use thiserror::Error;
 
@@ -11,8 +12,24 @@ pub enum Error {
     FileFormat(String),
     #[error("field {0} do not exist")]
     FieldNotFound(String),
-    #[error("feature not supported")]
-    NotSupported,
+    #[error("field {0} needs a memo file")]
+    MemoNotFound(String),
+    #[error("table {0} do not exist in the database")]
+    TableNotFound(String),
+    #[error("record {0} is out of bounds, there are {1} records")]
+    RecordOutOfBounds(u32, u32),
+    #[error("unable to deserialize: {0}")]
+    Deserialize(String),
+    #[error("{0} are not supported")]
+    NotSupported(String),
     #[error("invalid value for field: {0}")]
     Fieldvalue(String),
+    #[error("{0} is not {1}")]
+    UnexpectedType(String, &'static str),
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Deserialize(msg.to_string())
+    }
 }
@@ -1,6 +1,3 @@
-use byteorder::ReadBytesExt;
-use std::io::{ErrorKind, Read, Result as IOResult};
-
 pub mod dbf;
 pub mod errors;
 pub mod memo;
@@ -17,41 +14,14 @@ impl<'a> SliceUntilTerminator<'a> for [u8] {
     }
 }
 
-trait ReaderUntilTerminator {
-    fn read_until_terminator(&mut self, delimiter: &[u8]) -> IOResult<Vec<u8>>;
-}
-
-impl<R> ReaderUntilTerminator for R
-where
-    R: Read,
-{
-    fn read_until_terminator(&mut self, delimiter: &[u8]) -> IOResult<Vec<u8>> {
-        assert!(!delimiter.is_empty(), "delimiter must not be empty");
-
-        let mut output = vec![];
-        loop {
-            match self.read_u8() {
-                Ok(b) => {
-                    output.push(b);
-                    if output.len() >= delimiter.len() {
-                        let size = output.len() - delimiter.len();
-                        if output[size..] == *delimiter {
-                            output.truncate(size);
-                            return Ok(output);
-                        }
-                    }
-                }
-                Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(output),
-                Err(err) => return Err(err),
-            }
-        }
-    }
+#[cfg(test)]
+fn sample_path(name: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("samples")
+        .join(name)
 }
 
 #[cfg(test)]
 fn sample_file(name: &str) -> std::io::Result<std::fs::File> {
-    use std::fs::File;
-
-    let path = format!("{}/samples/{name}", env!("CARGO_MANIFEST_DIR"));
-    File::open(path)
+    std::fs::File::open(sample_path(name))
 }
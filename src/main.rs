@@ -1,10 +1,7 @@
 use scaling_dollop::dbf::reader::DbfReader;
-use std::fs::File;
 
 fn main() -> anyhow::Result<()> {
-    let file = File::open("samples/vfp.dbf")?;
-
-    let reader = DbfReader::from_reader(file)?;
+    let reader = DbfReader::open("samples/vfp.dbf")?;
     for field in reader.fields() {
         println!("{}: {:?}", field.name(), field.field_type());
     }
@@ -0,0 +1,277 @@
+use crate::errors::Error;
+use crate::errors::Error::FileFormat;
+use crate::memo::dbt::{
+    BLOCK_SIZE, Dbt3Memo, parse_dbt3_header, parse_dbt4_block, parse_dbt4_header,
+};
+use crate::memo::fpt::{parse_fpt_block, parse_fpt_header};
+use crate::memo::{BLOCK_START_SIZE, HEADER_SIZE, MemoHeader, MemoRead, MemoType};
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::SeekFrom;
+use std::pin::Pin;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+/// A memo read that can be awaited
+pub type MemoFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<(MemoType, Vec<u8>), Error>> + Send + 'a>>;
+
+/// Reads a memo field without blocking, the same as `MemoRead` does
+pub trait AsyncMemoRead: Send {
+    /// Reads a memo and the kind of data it holds,
+    /// only FPT files store it so for DBT files every memo is text
+    fn read_typed_memo(&mut self, index: u32) -> MemoFuture<'_>;
+
+    fn next_available_block(&self) -> u32;
+
+    /// Whether text memos are word wrapped with dBase soft returns,
+    /// the same as `MemoRead::has_soft_returns`
+    fn has_soft_returns(&self) -> bool {
+        false
+    }
+}
+
+/// Reads the bytes at the start of a memo file
+async fn read_header<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+) -> Result<[u8; HEADER_SIZE], Error> {
+    reader.seek(SeekFrom::Start(0)).await?;
+    let mut header = [0u8; HEADER_SIZE];
+    reader.read_exact(&mut header).await?;
+
+    Ok(header)
+}
+
+/// Reads the bytes at the start of a DBT4 or FPT block
+async fn read_block_start<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+    position: u64,
+) -> Result<[u8; BLOCK_START_SIZE], Error> {
+    reader.seek(SeekFrom::Start(position)).await?;
+    let mut start = [0u8; BLOCK_START_SIZE];
+    reader.read_exact(&mut start).await?;
+
+    Ok(start)
+}
+
+pub struct AsyncDbt3Reader<R: AsyncRead + AsyncSeek + Unpin + Send> {
+    next_block: u32,
+    reader: R,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin + Send> AsyncDbt3Reader<R> {
+    pub async fn from_reader(mut reader: R) -> Result<Self, Error> {
+        let MemoHeader { next_block, .. } = parse_dbt3_header(&read_header(&mut reader).await?);
+
+        Ok(Self { next_block, reader })
+    }
+
+    async fn read_memo(&mut self, index: u32) -> Result<(MemoType, Vec<u8>), Error> {
+        let position = (BLOCK_SIZE as u64) * (index as u64);
+        self.reader.seek(SeekFrom::Start(position)).await?;
+
+        let mut memo = Dbt3Memo::default();
+        let mut block = [0u8; BLOCK_SIZE as usize];
+        loop {
+            let read = self.reader.read(&mut block).await?;
+            if read == 0 || memo.push(&block[..read]) {
+                return Ok((MemoType::Text, memo.into_data()));
+            }
+        }
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin + Send> AsyncMemoRead for AsyncDbt3Reader<R> {
+    fn read_typed_memo(&mut self, index: u32) -> MemoFuture<'_> {
+        Box::pin(self.read_memo(index))
+    }
+
+    fn next_available_block(&self) -> u32 {
+        self.next_block
+    }
+    fn has_soft_returns(&self) -> bool {
+        true
+    }
+}
+
+pub struct AsyncDbt4Reader<R: AsyncRead + AsyncSeek + Unpin + Send> {
+    next_block: u32,
+    block_size: u32,
+    reader: R,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin + Send> AsyncDbt4Reader<R> {
+    pub async fn from_reader(mut reader: R) -> Result<Self, Error> {
+        let MemoHeader {
+            next_block,
+            block_size,
+        } = parse_dbt4_header(&read_header(&mut reader).await?);
+
+        Ok(Self {
+            next_block,
+            block_size,
+            reader,
+        })
+    }
+
+    async fn read_memo(&mut self, index: u32) -> Result<(MemoType, Vec<u8>), Error> {
+        let position = (self.block_size as u64) * (index as u64);
+        let length = parse_dbt4_block(&read_block_start(&mut self.reader, position).await?)?;
+
+        let mut output = Vec::with_capacity(length as usize);
+        (&mut self.reader)
+            .take(length)
+            .read_to_end(&mut output)
+            .await?;
+
+        Ok((MemoType::Text, output))
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin + Send> AsyncMemoRead for AsyncDbt4Reader<R> {
+    fn read_typed_memo(&mut self, index: u32) -> MemoFuture<'_> {
+        Box::pin(self.read_memo(index))
+    }
+
+    fn next_available_block(&self) -> u32 {
+        self.next_block
+    }
+    fn has_soft_returns(&self) -> bool {
+        true
+    }
+}
+
+pub struct AsyncFptReader<R: AsyncRead + AsyncSeek + Unpin + Send> {
+    reader: R,
+    block_size: u32,
+    next_block: u32,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin + Send> AsyncFptReader<R> {
+    pub async fn from_reader(mut reader: R) -> Result<Self, Error> {
+        let MemoHeader {
+            next_block,
+            block_size,
+        } = parse_fpt_header(&read_header(&mut reader).await?);
+
+        Ok(Self {
+            reader,
+            block_size,
+            next_block,
+        })
+    }
+
+    async fn read_memo(&mut self, index: u32) -> Result<(MemoType, Vec<u8>), Error> {
+        let position = (self.block_size as u64) * (index as u64);
+        let start = read_block_start(&mut self.reader, position).await?;
+        let (record_type, record_length) = parse_fpt_block(&start)?;
+
+        let mut data = Vec::with_capacity(record_length as usize);
+        (&mut self.reader)
+            .take(record_length)
+            .read_to_end(&mut data)
+            .await?;
+
+        Ok((record_type, data))
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin + Send> AsyncMemoRead for AsyncFptReader<R> {
+    fn read_typed_memo(&mut self, index: u32) -> MemoFuture<'_> {
+        Box::pin(self.read_memo(index))
+    }
+
+    fn next_available_block(&self) -> u32 {
+        self.next_block
+    }
+}
+
+/// Memos already read for a row, so the row can read them without blocking
+pub(crate) struct FetchedMemos {
+    pub(crate) memos: HashMap<u32, (MemoType, Vec<u8>)>,
+    pub(crate) next_block: u32,
+    pub(crate) soft_returns: bool,
+}
+
+impl MemoRead for FetchedMemos {
+    fn read_memo(&mut self, index: u32) -> Result<Vec<u8>, Error> {
+        Ok(self.read_typed_memo(index)?.1)
+    }
+
+    fn read_typed_memo(&mut self, index: u32) -> Result<(MemoType, Vec<u8>), Error> {
+        self.memos
+            .get(&index)
+            .cloned()
+            .ok_or(FileFormat(format!("memo block {index} was not read")))
+    }
+
+    fn next_available_block(&self) -> u32 {
+        self.next_block
+    }
+
+    fn has_soft_returns(&self) -> bool {
+        self.soft_returns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::memo::async_memo::{
+        AsyncDbt3Reader, AsyncDbt4Reader, AsyncFptReader, AsyncMemoRead,
+    };
+    use crate::memo::dbt::{Dbt3Reader, Dbt4Reader};
+    use crate::memo::fpt::FptReader;
+    use crate::memo::{MemoRead, MemoType};
+    use crate::{sample_file, sample_path};
+    use tokio::fs::File;
+
+    #[tokio::test]
+    async fn async_memos_match_memos() -> anyhow::Result<()> {
+        let mut dbt3 = Dbt3Reader::from_reader(sample_file("db3memo.dbt")?)?;
+        let mut async_dbt3 =
+            AsyncDbt3Reader::from_reader(File::open(sample_path("db3memo.dbt")).await?).await?;
+        assert_eq!(
+            dbt3.next_available_block(),
+            async_dbt3.next_available_block()
+        );
+
+        let mut dbt4 = Dbt4Reader::from_reader(sample_file("db4memo.dbt")?)?;
+        let mut async_dbt4 =
+            AsyncDbt4Reader::from_reader(File::open(sample_path("db4memo.dbt")).await?).await?;
+        assert_eq!(
+            dbt4.next_available_block(),
+            async_dbt4.next_available_block()
+        );
+
+        let mut fpt = FptReader::from_reader(sample_file("vfpmemo.fpt")?)?;
+        let mut async_fpt =
+            AsyncFptReader::from_reader(File::open(sample_path("vfpmemo.fpt")).await?).await?;
+        assert_eq!(fpt.next_available_block(), async_fpt.next_available_block());
+
+        for block in 1..dbt3.next_available_block() {
+            let (kind, data) = async_dbt3.read_typed_memo(block).await?;
+            assert_eq!(MemoType::Text, kind);
+            assert_eq!(dbt3.read_memo(block)?, data);
+        }
+        for block in 1..dbt4.next_available_block() {
+            let (_, data) = async_dbt4.read_typed_memo(block).await?;
+            assert_eq!(dbt4.read_memo(block)?, data);
+        }
+
+        // blocks in the middle of a memo have no valid record type
+        let mut memos = 0;
+        for block in 0..fpt.next_available_block() {
+            let memo = async_fpt.read_typed_memo(block).await;
+            let memo = memo.map_err(|err| err.to_string());
+            let expected = fpt.read_typed_memo(block).map_err(|err| err.to_string());
+            assert_eq!(expected, memo, "block {block}");
+            memos += usize::from(memo.is_ok());
+        }
+        assert!(memos > 0);
+        assert_eq!(
+            (MemoType::Text, b"Just a brief note.".to_vec()),
+            async_fpt.read_typed_memo(8).await?
+        );
+
+        Ok(())
+    }
+}
@@ -1,11 +1,66 @@
-use crate::ReaderUntilTerminator;
 use crate::errors::Error;
 use crate::errors::Error::Conversion;
-use crate::memo::MemoRead;
-use byteorder::{LittleEndian, ReadBytesExt};
+use crate::memo::{BLOCK_START_SIZE, HEADER_SIZE, MemoHeader, MemoRead, read_header};
+use byteorder::{ByteOrder, LittleEndian};
 use std::io::{Read, Seek, SeekFrom};
 
-const BLOCK_SIZE: u32 = 512;
+pub(crate) const BLOCK_SIZE: u32 = 512;
+const TERMINATOR: &[u8] = &[0x1a, 0x1a];
+
+/// Parses the header of a DBT3 file, its blocks are always 512 bytes
+pub(crate) fn parse_dbt3_header(header: &[u8; HEADER_SIZE]) -> MemoHeader {
+    MemoHeader {
+        next_block: LittleEndian::read_u32(&header[0..4]),
+        block_size: BLOCK_SIZE,
+    }
+}
+
+/// Parses the header of a DBT4 file, its block size is at offset 20
+pub(crate) fn parse_dbt4_header(header: &[u8; HEADER_SIZE]) -> MemoHeader {
+    MemoHeader {
+        next_block: LittleEndian::read_u32(&header[0..4]),
+        block_size: LittleEndian::read_u16(&header[20..22]) as u32,
+    }
+}
+
+/// Parses the start of a DBT4 block and returns how long its memo is
+pub(crate) fn parse_dbt4_block(start: &[u8; BLOCK_START_SIZE]) -> Result<u64, Error> {
+    // grab memo length, this is total length of the field!
+    let length = LittleEndian::read_u32(&start[4..8]);
+    let length = length
+        .checked_sub(BLOCK_START_SIZE as u32)
+        .ok_or(Conversion)?;
+
+    Ok(length as u64)
+}
+
+/// A DBT3 memo read a block at a time, it goes on until its terminator
+#[derive(Default)]
+pub(crate) struct Dbt3Memo {
+    data: Vec<u8>,
+}
+
+impl Dbt3Memo {
+    /// Adds the next block of the memo, `true` once its terminator was found
+    pub(crate) fn push(&mut self, block: &[u8]) -> bool {
+        // the terminator may start at the end of the last block
+        let searched = self.data.len().saturating_sub(TERMINATOR.len() - 1);
+        self.data.extend_from_slice(block);
+
+        let end = self.data[searched..]
+            .windows(TERMINATOR.len())
+            .position(|window| window == TERMINATOR);
+        if let Some(end) = end {
+            self.data.truncate(searched + end);
+        }
+
+        end.is_some()
+    }
+
+    pub(crate) fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
 
 pub struct Dbt3Reader<R: Read + Seek> {
     next_block: u32,
@@ -14,8 +69,7 @@ pub struct Dbt3Reader<R: Read + Seek> {
 
 impl<R: Read + Seek> Dbt3Reader<R> {
     pub fn from_reader(mut reader: R) -> Result<Self, Error> {
-        reader.seek(SeekFrom::Start(0))?;
-        let next_block = reader.read_u32::<LittleEndian>()?;
+        let MemoHeader { next_block, .. } = parse_dbt3_header(&read_header(&mut reader)?);
 
         Ok(Self { next_block, reader })
     }
@@ -25,14 +79,24 @@ impl<R: Read + Seek> MemoRead for Dbt3Reader<R> {
     fn read_memo(&mut self, index: u32) -> Result<Vec<u8>, Error> {
         let position = (BLOCK_SIZE as u64) * (index as u64);
         self.reader.seek(SeekFrom::Start(position))?;
-        let data = self.reader.read_until_terminator(&[0x1a, 0x1a])?;
 
-        Ok(data)
+        let mut memo = Dbt3Memo::default();
+        let mut block = [0u8; BLOCK_SIZE as usize];
+        loop {
+            let read = self.reader.read(&mut block)?;
+            if read == 0 || memo.push(&block[..read]) {
+                return Ok(memo.into_data());
+            }
+        }
     }
 
     fn next_available_block(&self) -> u32 {
         self.next_block
     }
+
+    fn has_soft_returns(&self) -> bool {
+        true
+    }
 }
 
 pub struct Dbt4Reader<R: Read + Seek> {
@@ -42,14 +106,11 @@ pub struct Dbt4Reader<R: Read + Seek> {
 }
 
 impl<R: Read + Seek> Dbt4Reader<R> {
-    pub fn from_reader(mut reader: R) -> Result<Self, Error>
-    where
-        Self: Sized,
-    {
-        reader.seek(SeekFrom::Start(0))?;
-        let next_block = reader.read_u32::<LittleEndian>()?;
-        reader.seek(SeekFrom::Start(20))?;
-        let block_size = reader.read_u16::<LittleEndian>()? as u32;
+    pub fn from_reader(mut reader: R) -> Result<Self, Error> {
+        let MemoHeader {
+            next_block,
+            block_size,
+        } = parse_dbt4_header(&read_header(&mut reader)?);
 
         Ok(Self {
             next_block,
@@ -62,16 +123,13 @@ impl<R: Read + Seek> Dbt4Reader<R> {
 impl<R: Read + Seek> MemoRead for Dbt4Reader<R> {
     fn read_memo(&mut self, index: u32) -> Result<Vec<u8>, Error> {
         let position = index as u64 * self.block_size as u64;
-        self.reader.seek(SeekFrom::Start(position + 4))?;
+        self.reader.seek(SeekFrom::Start(position))?;
+        let mut start = [0u8; BLOCK_START_SIZE];
+        self.reader.read_exact(&mut start)?;
+        let length = parse_dbt4_block(&start)?;
 
-        // grab memo length, this is total length of the field!
-        let length = self.reader.read_u32::<LittleEndian>()?;
-        let length = length.checked_sub(8).ok_or(Conversion)?;
         let mut output = Vec::with_capacity(length as usize);
-        self.reader
-            .by_ref()
-            .take(length as u64)
-            .read_to_end(&mut output)?;
+        self.reader.by_ref().take(length).read_to_end(&mut output)?;
 
         Ok(output)
     }
@@ -79,6 +137,10 @@ impl<R: Read + Seek> MemoRead for Dbt4Reader<R> {
     fn next_available_block(&self) -> u32 {
         self.next_block
     }
+
+    fn has_soft_returns(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,28 @@
 use crate::errors::Error;
-use crate::memo::MemoRead;
-use byteorder::{BigEndian, ReadBytesExt};
+use crate::errors::Error::FileFormat;
+use crate::memo::{BLOCK_START_SIZE, HEADER_SIZE, MemoHeader, MemoRead, MemoType, read_header};
+use byteorder::{BigEndian, ByteOrder};
 use std::io::{Read, Seek, SeekFrom};
 
+/// Parses the header of a FPT file, its numbers are big endian
+pub(crate) fn parse_fpt_header(header: &[u8; HEADER_SIZE]) -> MemoHeader {
+    MemoHeader {
+        next_block: BigEndian::read_u32(&header[0..4]),
+        block_size: BigEndian::read_u16(&header[6..8]) as u32,
+    }
+}
+
+/// Parses the start of a FPT block, the kind of memo and how long it is
+pub(crate) fn parse_fpt_block(start: &[u8; BLOCK_START_SIZE]) -> Result<(MemoType, u64), Error> {
+    let record_type = BigEndian::read_u32(&start[0..4]);
+    let record_type = MemoType::from_repr(record_type).ok_or(FileFormat(format!(
+        "invalid memo record type: {record_type}"
+    )))?;
+    let record_length = BigEndian::read_u32(&start[4..8]) as u64;
+
+    Ok((record_type, record_length))
+}
+
 pub struct FptReader<R: Read + Seek> {
     reader: R,
     block_size: u32,
@@ -11,9 +31,10 @@ pub struct FptReader<R: Read + Seek> {
 
 impl<R: Read + Seek> FptReader<R> {
     pub fn from_reader(mut reader: R) -> Result<Self, Error> {
-        let next_block = reader.read_u32::<BigEndian>()?;
-        reader.seek(SeekFrom::Current(2))?;
-        let block_size = reader.read_u16::<BigEndian>()? as u32;
+        let MemoHeader {
+            next_block,
+            block_size,
+        } = parse_fpt_header(&read_header(&mut reader)?);
 
         Ok(Self {
             reader,
@@ -25,12 +46,17 @@ impl<R: Read + Seek> FptReader<R> {
 
 impl<R: Read + Seek> MemoRead for FptReader<R> {
     fn read_memo(&mut self, index: u32) -> Result<Vec<u8>, Error> {
+        let (_, data) = self.read_typed_memo(index)?;
+
+        Ok(data)
+    }
+
+    fn read_typed_memo(&mut self, index: u32) -> Result<(MemoType, Vec<u8>), Error> {
         let position = (self.block_size as u64) * (index as u64);
         self.reader.seek(SeekFrom::Start(position))?;
-
-        // for now we don't use record type, we will support different types later
-        let _record_type = self.reader.read_u32::<BigEndian>()?;
-        let record_length = self.reader.read_u32::<BigEndian>()? as u64;
+        let mut start = [0u8; BLOCK_START_SIZE];
+        self.reader.read_exact(&mut start)?;
+        let (record_type, record_length) = parse_fpt_block(&start)?;
 
         let mut data = Vec::with_capacity(record_length as usize);
 
@@ -39,7 +65,7 @@ impl<R: Read + Seek> MemoRead for FptReader<R> {
             .take(record_length)
             .read_to_end(&mut data)?;
 
-        Ok(data)
+        Ok((record_type, data))
     }
 
     fn next_available_block(&self) -> u32 {
@@ -49,9 +75,10 @@ impl<R: Read + Seek> MemoRead for FptReader<R> {
 
 #[cfg(test)]
 mod tests {
-    use crate::memo::MemoRead;
     use crate::memo::fpt::FptReader;
+    use crate::memo::{MemoRead, MemoType};
     use crate::sample_file;
+    use std::io::Cursor;
 
     #[test]
     fn test_fpt1_header() -> anyhow::Result<()> {
@@ -85,4 +112,47 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_fpt_record_types() -> anyhow::Result<()> {
+        // header with 64 bytes blocks, then a text, a picture and an object block
+        let mut data = vec![0u8; 512];
+        data[..8].copy_from_slice(&[0x00, 0x00, 0x00, 0x0b, 0x00, 0x00, 0x00, 0x40]);
+        for (record_type, content) in [(1u32, &b"text"[..]), (0, &[0xff, 0xd8]), (2, &[0x00])] {
+            let mut block = vec![0u8; 64];
+            block[..4].copy_from_slice(&record_type.to_be_bytes());
+            block[4..8].copy_from_slice(&(content.len() as u32).to_be_bytes());
+            block[8..8 + content.len()].copy_from_slice(content);
+            data.extend(block);
+        }
+
+        let mut reader = FptReader::from_reader(Cursor::new(data))?;
+
+        assert_eq!(
+            (MemoType::Text, b"text".to_vec()),
+            reader.read_typed_memo(8)?
+        );
+        assert_eq!(
+            (MemoType::Picture, vec![0xff, 0xd8]),
+            reader.read_typed_memo(9)?
+        );
+        assert_eq!((MemoType::Object, vec![0x00]), reader.read_typed_memo(10)?);
+
+        // plain reads keep returning the data
+        assert_eq!(vec![0xff, 0xd8], reader.read_memo(9)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fpt_text_records() -> anyhow::Result<()> {
+        let mut file = sample_file("vfpmemo.fpt")?;
+        let mut reader = FptReader::from_reader(&mut file)?;
+
+        let (record_type, data) = reader.read_typed_memo(8)?;
+        assert_eq!(MemoType::Text, record_type);
+        assert_eq!(b"Just a brief note.".to_vec(), data);
+
+        Ok(())
+    }
 }
@@ -1,12 +1,69 @@
+#[cfg(feature = "async")]
+pub mod async_memo;
 pub mod dbt;
 pub mod fpt;
+mod ole;
 
 use crate::errors::Error;
+pub(crate) use ole::unpack_ole;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::{Arc, Mutex};
+use strum::{Display, FromRepr};
+
+/// The kind of data stored in a memo block
+#[derive(Clone, Copy, Debug, PartialEq, FromRepr, Display)]
+#[repr(u32)]
+pub enum MemoType {
+    #[strum(to_string = "picture")]
+    Picture = 0,
+    #[strum(to_string = "text")]
+    Text = 1,
+    #[strum(to_string = "object")]
+    Object = 2,
+}
 
 /// Reads a memo field
 pub trait MemoRead {
     fn read_memo(&mut self, index: u32) -> Result<Vec<u8>, Error>;
+
+    /// Reads a memo and the kind of data it holds,
+    /// only FPT files store it so by default every memo is text
+    fn read_typed_memo(&mut self, index: u32) -> Result<(MemoType, Vec<u8>), Error> {
+        Ok((MemoType::Text, self.read_memo(index)?))
+    }
+
     fn next_available_block(&self) -> u32;
+
+    /// Whether text memos are word wrapped with dBase soft returns (0x8d 0x0a),
+    /// only DBT files have them, elsewhere 0x8d is a character like 'ì'
+    fn has_soft_returns(&self) -> bool {
+        false
+    }
+}
+
+/// A memo reader shared between a table and the rows read from it
+pub(crate) type SharedMemo = Arc<Mutex<dyn MemoRead + Send>>;
+
+/// Bytes at the start of a memo file, enough for every header we parse
+pub(crate) const HEADER_SIZE: usize = 22;
+
+/// Bytes at the start of a DBT4 or FPT block, before the memo itself
+pub(crate) const BLOCK_START_SIZE: usize = 8;
+
+/// What readers need from the header of a memo file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct MemoHeader {
+    pub(crate) next_block: u32,
+    pub(crate) block_size: u32,
+}
+
+/// Reads the bytes at the start of a memo file
+pub(crate) fn read_header<R: Read + Seek>(reader: &mut R) -> Result<[u8; HEADER_SIZE], Error> {
+    reader.seek(SeekFrom::Start(0))?;
+    let mut header = [0u8; HEADER_SIZE];
+    reader.read_exact(&mut header)?;
+
+    Ok(header)
 }
 
 /// Represent a value from a memo field
@@ -0,0 +1,64 @@
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::io::{Cursor, Seek, SeekFrom};
+
+const EMBEDDED: u32 = 0x02;
+
+/// Unpacks the document embedded in an OLE 1 object, the way General
+/// fields store them, `None` when it is linked or not an OLE object
+pub(crate) fn unpack_ole(data: &[u8]) -> Option<&[u8]> {
+    let mut cursor = Cursor::new(data);
+
+    let _ole_version = cursor.read_u32::<LittleEndian>().ok()?;
+    if cursor.read_u32::<LittleEndian>().ok()? != EMBEDDED {
+        return None;
+    }
+
+    // class, topic and item names are strings with their length first
+    for _ in 0..3 {
+        let length = cursor.read_u32::<LittleEndian>().ok()?;
+        cursor.seek(SeekFrom::Current(length as i64)).ok()?;
+    }
+
+    let size = cursor.read_u32::<LittleEndian>().ok()? as usize;
+    let start = cursor.position() as usize;
+
+    data.get(start..start.checked_add(size)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::memo::ole::unpack_ole;
+
+    fn ole_object(format: u32, native: &[u8]) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend(0x0501u32.to_le_bytes());
+        data.extend(format.to_le_bytes());
+        for name in [&b"PBrush\0"[..], b"\0", b"\0"] {
+            data.extend((name.len() as u32).to_le_bytes());
+            data.extend(name);
+        }
+        data.extend((native.len() as u32).to_le_bytes());
+        data.extend(native);
+        // presentation data we don't need
+        data.extend([0x01, 0x05, 0x00, 0x00]);
+        data
+    }
+
+    #[test]
+    fn unpack_embedded_objects() {
+        let data = ole_object(2, b"BM\x00\x01");
+        assert_eq!(Some(&b"BM\x00\x01"[..]), unpack_ole(&data));
+    }
+
+    #[test]
+    fn keep_other_objects() {
+        // linked objects have no document
+        assert_eq!(None, unpack_ole(&ole_object(1, b"")));
+
+        // neither do truncated ones
+        let data = ole_object(2, b"BM\x00\x01");
+        assert_eq!(None, unpack_ole(&data[..data.len() - 6]));
+
+        assert_eq!(None, unpack_ole(b"BM"));
+    }
+}